@@ -1,4 +1,5 @@
 use std::io::stdin;
+use std::process::exit;
 
 use futures::prelude::*;
 use log::*;
@@ -10,7 +11,9 @@ use tokio_serde::formats::*;
 use tokio_serde::Framed;
 use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
 
+use optcg::card::Card;
 use optcg::game::*;
+use optcg::locale::*;
 use optcg::player::*;
 use optcg::{print_hand, PlayerAction, ServerMessage};
 
@@ -24,6 +27,48 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     )
     .unwrap();
 
+    // `--locale ja` shows the client and card names in Japanese, and `--deck <file>` picks the
+    // deck list to play with, in any format `optcg-deck` reads.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let locale = match args.iter().position(|a| a == "--locale") {
+        Some(i) => match args.get(i + 1).map(|code| code.parse::<Locale>()) {
+            Some(Ok(locale)) => locale,
+            Some(Err(e)) => {
+                eprintln!("{e}");
+                exit(2);
+            }
+            None => {
+                eprintln!("missing value for `--locale`");
+                exit(2);
+            }
+        },
+        None => Locale::En,
+    };
+    let messages = match MessageCatalogue::load(LOCALE_DIR, locale) {
+        Ok(messages) => messages,
+        Err(e) => {
+            eprintln!("{e}");
+            exit(1);
+        }
+    };
+    let deck_path = match args.iter().position(|a| a == "--deck") {
+        Some(i) => match args.get(i + 1) {
+            Some(deck_path) => deck_path.as_str(),
+            None => {
+                eprintln!("missing value for `--deck`");
+                exit(2);
+            }
+        },
+        None => "sample_deck.txt",
+    };
+    let deck_list = match std::fs::read_to_string(deck_path) {
+        Ok(deck_list) => deck_list,
+        Err(e) => {
+            eprintln!("{deck_path}: {e}");
+            exit(1);
+        }
+    };
+
     let mut stream = TcpStream::connect("127.0.0.1:8080").await?;
     debug!("Connected to server.");
 
@@ -32,6 +77,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Box::new(Player::empty()),
         Box::new(Player::empty()),
         Box::new(PublicPlayfieldState::empty()),
+        messages,
+        deck_list,
         &mut stream,
     );
 
@@ -46,6 +93,8 @@ struct Client<'stream> {
     this_player: Box<Player>,
     other_player: Box<Player>,
     public_playfield_state: Box<PublicPlayfieldState>,
+    messages: MessageCatalogue,
+    deck_list: String,
     writer: Framed<
         FramedWrite<WriteHalf<'stream>, LengthDelimitedCodec>,
         Value,
@@ -66,6 +115,8 @@ impl<'stream> Client<'stream> {
         this_player: Box<Player>,
         other_player: Box<Player>,
         public_playfield_state: Box<PublicPlayfieldState>,
+        messages: MessageCatalogue,
+        deck_list: String,
         socket: &'stream mut TcpStream,
     ) -> Self {
         let (rx, tx) = socket.split();
@@ -81,6 +132,8 @@ impl<'stream> Client<'stream> {
             this_player,
             other_player,
             public_playfield_state,
+            messages,
+            deck_list,
             writer,
             reader,
         }
@@ -101,28 +154,48 @@ impl<'stream> Client<'stream> {
                 ServerMessage::PlayerId(id) => {
                     self.this_id = id;
                 }
-                ServerMessage::RequestDeck => {}
+                ServerMessage::RequestDeck => {
+                    return self.send_action(PlayerAction::ReportDeck(self.deck_list.clone())).await;
+                }
+                ServerMessage::DeckRejected(errors) => {
+                    println!("{}", self.messages.get("deck_rejected"));
+                    for error in errors.iter() {
+                        println!("  {}", self.messages.deck_error(error));
+                    }
+                    self.choose_deck_file();
+                }
                 ServerMessage::QueryMulligan => {
-                    print_hand(&self.this_player.hand);
+                    print_hand(&self.this_player.hand, &self.messages);
                     return self.respond_to_query_mulligan().await;
                 }
                 ServerMessage::TakeMainAction => {
                     return self.respond_to_take_main_action().await;
                 }
                 ServerMessage::InsufficientDon => {
-                    println!("Insufficient DON!! to play this card.");
+                    println!("{}", self.messages.get("insufficient_don"));
                 }
                 ServerMessage::InvalidTarget => {
-                    println!("Invalid target.");
+                    println!("{}", self.messages.get("invalid_target"));
                 }
                 ServerMessage::DiscardCharacter => {
                     return self.respond_to_discard_character().await;
                 }
                 ServerMessage::CannotPlayCounterEventDuringMainPhase => {
-                    println!("Cannot play a counter event during the main phase.");
+                    println!("{}", self.messages.get("cannot_play_counter_event"));
                 }
                 ServerMessage::NoTargetsMeetConditions => {
-                    println!("No targets meet the conditions for this effect.");
+                    println!("{}", self.messages.get("no_targets_meet_conditions"));
+                }
+                ServerMessage::CannotAttack => {
+                    println!("{}", self.messages.get("cannot_attack"));
+                }
+                ServerMessage::GameOver(winner) => {
+                    if winner == self.this_id {
+                        println!("{}", self.messages.get("game_won"));
+                    } else {
+                        println!("{}", self.messages.get("game_lost"));
+                    }
+                    exit(0);
                 }
                 ServerMessage::QueryTargetOpposingCharacter => {
                     return self.respond_to_query_target_opposing_character().await;
@@ -132,6 +205,12 @@ impl<'stream> Client<'stream> {
                         .respond_to_query_target_self_character_or_leader()
                         .await;
                 }
+                ServerMessage::QueryActivateTrigger(card) => {
+                    return self.respond_to_query_activate_trigger(card).await;
+                }
+                ServerMessage::QueryReplacementEffect(effects) => {
+                    return self.respond_to_query_replacement_effect(effects).await;
+                }
                 ServerMessage::PlayerDataPayload(player) => {
                     self.this_player = player;
                 }
@@ -146,8 +225,25 @@ impl<'stream> Client<'stream> {
         }
     }
 
+    // the server asks for a deck again after rejecting one, so this only has to load a new list.
+    pub fn choose_deck_file(&mut self) {
+        loop {
+            println!("{}", self.messages.get("deck_file_prompt"));
+            let mut input = String::new();
+            stdin().read_line(&mut input).unwrap();
+
+            match std::fs::read_to_string(input.trim()) {
+                Ok(deck_list) => {
+                    self.deck_list = deck_list;
+                    return;
+                }
+                Err(e) => println!("{}: {e}", input.trim()),
+            }
+        }
+    }
+
     pub async fn respond_to_query_mulligan(&mut self) {
-        println!("Mulligan? [y/N]  ");
+        println!("{}", self.messages.get("mulligan_prompt"));
         let mut input = String::new();
         stdin().read_line(&mut input).unwrap();
         loop {
@@ -159,21 +255,51 @@ impl<'stream> Client<'stream> {
         }
     }
 
+    pub async fn respond_to_query_activate_trigger(&mut self, card: Box<Card>) {
+        println!("{}", card.localised_name(self.messages.locale));
+        println!("{}", card.localised_text(self.messages.locale));
+        println!("{}", self.messages.get("activate_trigger_prompt"));
+        let mut input = String::new();
+        stdin().read_line(&mut input).unwrap();
+        match input.trim().to_lowercase().as_str() {
+            "y" => self.send_action(PlayerAction::ActivateTrigger).await,
+            _ => self.send_action(PlayerAction::NoAction).await,
+        }
+    }
+
+    pub async fn respond_to_query_replacement_effect(&mut self, effects: Vec<Effect>) {
+        println!("{}", self.messages.get("replacement_effect_prompt"));
+        for (i, effect) in effects.iter().enumerate() {
+            println!("{i}: {}", effect);
+        }
+        let mut input = String::new();
+        stdin().read_line(&mut input).unwrap();
+        match input.trim().parse::<usize>() {
+            Ok(i) if i < effects.len() => {
+                self.send_action(PlayerAction::ApplyReplacementEffect(i))
+                    .await
+            }
+            _ => self.send_action(PlayerAction::NoAction).await,
+        }
+    }
+
     pub async fn respond_to_take_main_action(&mut self) {
         loop {
-            println!("Hand: ");
-            print_hand(&self.this_player.hand);
+            println!("{}", self.messages.get("hand"));
+            print_hand(&self.this_player.hand, &self.messages);
 
-            println!("Action: ");
+            println!("{}", self.messages.get("action"));
             let mut input = String::new();
             stdin().read_line(&mut input).unwrap();
-            let main_action = parse_main_action(input.trim().to_lowercase().as_str());
-            return self.send_action(main_action).await;
+            // `None` means the input was malformed and a usage message was printed, so ask again.
+            if let Some(main_action) = parse_main_action(input.trim().to_lowercase().as_str(), &self.messages) {
+                return self.send_action(main_action).await;
+            }
         }
     }
 
     pub async fn respond_to_query_target_opposing_character(&mut self) {
-        println!("Select a Character to target:");
+        println!("{}", self.messages.get("select_character_target"));
         let target_idx: usize;
         match self.this_id {
             Turn::P1 => {
@@ -211,7 +337,7 @@ impl<'stream> Client<'stream> {
     }
 
     pub async fn respond_to_query_target_self_character_or_leader(&mut self) {
-        println!("Select a Character to target:");
+        println!("{}", self.messages.get("select_character_target"));
         let target_idx: char;
         match self.this_id {
             Turn::P1 => {
@@ -223,7 +349,11 @@ impl<'stream> Client<'stream> {
                 {
                     println!("{i}: {}", character);
                 }
-                println!("L: Leader {}", self.this_player.leader);
+                println!(
+                    "{}",
+                    self.messages
+                        .format("leader_target", &[("card", &self.this_player.leader.to_string())])
+                );
                 let mut input = String::new();
                 stdin().read_line(&mut input).unwrap();
 
@@ -238,7 +368,11 @@ impl<'stream> Client<'stream> {
                 {
                     println!("{i}: {}", character);
                 }
-                println!("L: Leader {}", self.this_player.leader);
+                println!(
+                    "{}",
+                    self.messages
+                        .format("leader_target", &[("card", &self.this_player.leader.to_string())])
+                );
                 let mut input = String::new();
                 stdin().read_line(&mut input).unwrap();
 
@@ -251,7 +385,7 @@ impl<'stream> Client<'stream> {
     }
 
     pub async fn respond_to_discard_character(&mut self) {
-        println!("Discard which character?");
+        println!("{}", self.messages.get("discard_character_prompt"));
         match self.this_id {
             Turn::P1 => loop {
                 for (i, character) in self
@@ -271,7 +405,7 @@ impl<'stream> Client<'stream> {
                 }
                 let target_idx = target_idx.unwrap();
                 if target_idx > self.public_playfield_state.p1_character_area.len() - 1 {
-                    println!("Invalid target.");
+                    println!("{}", self.messages.get("invalid_target"));
                     continue;
                 }
                 self.send_action(PlayerAction::DiscardCharacter(target_idx))
@@ -296,7 +430,7 @@ impl<'stream> Client<'stream> {
                 }
                 let target_idx = target_idx.unwrap();
                 if target_idx > self.public_playfield_state.p1_character_area.len() - 1 {
-                    println!("Invalid target.");
+                    println!("{}", self.messages.get("invalid_target"));
                     continue;
                 }
                 self.send_action(PlayerAction::DiscardCharacter(target_idx))
@@ -308,7 +442,7 @@ impl<'stream> Client<'stream> {
     }
 }
 
-fn parse_main_action(input: &str) -> PlayerAction {
+fn parse_main_action(input: &str, messages: &MessageCatalogue) -> Option<PlayerAction> {
     use PlayerAction::*;
 
     debug!("Parsing Main Action");
@@ -316,44 +450,55 @@ fn parse_main_action(input: &str) -> PlayerAction {
     let cleaned_input = cleaned_input.as_str();
 
     if cleaned_input == "" {
-        return NoAction;
+        return Some(NoAction);
     }
 
     let words: Vec<_> = cleaned_input.split_whitespace().collect();
 
     match words[0] {
         "help" => {
-            println!("These are the following commands you can use during the main phase:");
-            println!("help - Show this help message.");
-            println!("hand - Show your hand.");
-            println!("board - Examine the current board state.");
-            println!("examine <place> <card number> - Examine a card that is in your hand or face up on the board for its full text.");
-            println!("play <card number> - Play a card from your hand.");
-            println!("activate <card number, 'L', or 'S'> - Activate a card effect on the board.");
-            println!("attach <card number or 'L'> - Attach a DON!! card from the active DON!! area to your leader or a character in play.");
-            println!("battle <card number or 'L'> - Initiate a battle with your leader or an active character in play.");
-            println!("end - End your turn.");
+            for key in [
+                "help_title",
+                "help_help",
+                "help_hand",
+                "help_board",
+                "help_examine",
+                "help_play",
+                "help_activate",
+                "help_attach",
+                "help_battle",
+                "help_end",
+            ] {
+                println!("{}", messages.get(key));
+            }
             println!();
-            println!("Press enter to continue...");
+            println!("{}", messages.get("press_enter"));
             let mut _temp = String::new();
             stdin().read_line(&mut _temp).unwrap();
-            NoAction
+            Some(NoAction)
         }
-        "end" => End,
+        "end" => Some(End),
         "play" => {
             if words.len() < 2 {
-                return NoAction;
+                return Some(NoAction);
             }
             let card_number = words[1].parse::<usize>().unwrap();
-            MainPlayCard(card_number)
+            Some(MainPlayCard(card_number))
         }
         "activate" => {
             if words.len() < 2 {
-                return NoAction;
+                return Some(NoAction);
             }
             let card_id = words[1].parse::<char>().unwrap();
-            MainActivateCardEffect(card_id)
+            Some(MainActivateCardEffect(card_id))
         }
-        _ => NoAction,
+        "battle" => match (words.len(), words.get(1).and_then(|w| w.parse::<char>().ok())) {
+            (2, Some(card_id)) if card_id == 'l' || card_id.is_ascii_digit() => Some(MainBattle(card_id)),
+            _ => {
+                println!("{}", messages.get("help_battle"));
+                None
+            }
+        },
+        _ => Some(NoAction),
     }
 }
@@ -0,0 +1,148 @@
+use std::process::exit;
+
+use optcg::card_database::*;
+use optcg::card_definition::*;
+use optcg::card_import::*;
+use optcg::card_validation::*;
+use optcg::utils::*;
+
+// Moves card data between `assets/card_data` and a single JSON file holding an array of cards.
+// The files in `assets/card_data` are the source of truth; nothing else writes to them.
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("export") => export(args.get(1)),
+        Some("import") if args.len() >= 2 => import(&args[1], args.iter().any(|a| a == "--force")),
+        Some("import-csv") if args.len() >= 2 => {
+            import_csv(&args[1], args.iter().any(|a| a == "--force"))
+        }
+        Some("migrate") => migrate_files(),
+        Some("validate") => validate(),
+        _ => {
+            print_usage();
+            exit(2);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("{e}");
+        exit(1);
+    }
+}
+
+fn print_usage() {
+    eprintln!("usage: optcg-cards export [<file>]          Write every card to one JSON file, or stdout.");
+    eprintln!("       optcg-cards import <file> [--force]  Write every card in a JSON file into {CARD_DATA_DIR}.");
+    eprintln!("                                            Existing files are only replaced with --force.");
+    eprintln!("       optcg-cards import-csv <file> [--force]");
+    eprintln!("                                            Write every card in a CSV or TSV card list into {CARD_DATA_DIR}.");
+    eprintln!("       optcg-cards migrate                  Upgrade card files in {CARD_DATA_DIR} to schema version {CARD_SCHEMA_VERSION}.");
+    eprintln!("       optcg-cards validate                 Check every card file in {CARD_DATA_DIR} and list all problems.");
+}
+
+fn export(path: Option<&String>) -> Result<(), CardDataError> {
+    let definitions: Vec<CardDefinition> = load_card_definitions(CARD_DATA_DIR)?
+        .into_iter()
+        .map(|(_, definition)| definition)
+        .collect();
+    let card_data =
+        serde_json::to_string_pretty(&definitions).map_err(|e| CardDataError::Json(e.to_string()))?;
+
+    match path {
+        Some(path) => {
+            std::fs::write(path, card_data).map_err(|e| {
+                CardDataError::InFile(path.clone(), Box::new(CardDataError::Io(e.to_string())))
+            })?;
+            println!("Exported {} cards to {path}", definitions.len());
+        }
+        None => println!("{card_data}"),
+    }
+
+    Ok(())
+}
+
+fn import(path: &str, overwrite: bool) -> Result<(), CardDataError> {
+    let in_file = |e: CardDataError| CardDataError::InFile(path.to_string(), Box::new(e));
+
+    let card_data = std::fs::read_to_string(path).map_err(|e| in_file(CardDataError::Io(e.to_string())))?;
+    let entries: Vec<serde_json::Value> =
+        serde_json::from_str(&card_data).map_err(|e| in_file(CardDataError::Json(e.to_string())))?;
+
+    // check every card before writing any of them. Older schemas are upgraded on the way in.
+    let mut definitions = vec![];
+    for (i, entry) in entries.iter().enumerate() {
+        let in_card = |e: CardDataError| in_file(CardDataError::InFile(format!("card {i}"), Box::new(e)));
+        let definition = load_card_definition(&entry.to_string()).map_err(in_card)?;
+        definition
+            .to_cards()
+            .map_err(|e| in_card(CardDataError::Script(e)))?;
+        definitions.push(definition);
+    }
+
+    write_definitions(&definitions, overwrite)
+}
+
+fn import_csv(path: &str, overwrite: bool) -> Result<(), CardDataError> {
+    let card_list = std::fs::read_to_string(path)
+        .map_err(|e| CardDataError::InFile(path.to_string(), Box::new(CardDataError::Io(e.to_string()))))?;
+
+    // a row with a problem stops the whole import, so a card list is never half written.
+    let (definitions, errors) = import_card_list(&card_list, detect_delimiter(&card_list));
+    if !errors.is_empty() {
+        for error in errors.iter() {
+            eprintln!("{path}: {error}");
+        }
+        eprintln!("{} problem(s) found, no cards were written.", errors.len());
+        exit(1);
+    }
+
+    write_definitions(&definitions, overwrite)
+}
+
+fn write_definitions(definitions: &[CardDefinition], overwrite: bool) -> Result<(), CardDataError> {
+    if !overwrite {
+        for definition in definitions.iter() {
+            let existing = format!("{}/{}", CARD_DATA_DIR, card_file_name(definition));
+            if std::path::Path::new(&existing).exists() {
+                return Err(CardDataError::InFile(
+                    existing,
+                    Box::new(CardDataError::Io("file already exists, use --force to replace it".into())),
+                ));
+            }
+        }
+    }
+
+    for definition in definitions.iter() {
+        let written = write_card_file(CARD_DATA_DIR, definition, true)?;
+        println!("Wrote {written}");
+    }
+
+    Ok(())
+}
+
+fn migrate_files() -> Result<(), CardDataError> {
+    let upgraded = migrate_card_files(CARD_DATA_DIR)?;
+    for file in upgraded.iter() {
+        println!("Upgraded {file}");
+    }
+    println!("{} file(s) upgraded to schema version {CARD_SCHEMA_VERSION}.", upgraded.len());
+
+    Ok(())
+}
+
+fn validate() -> Result<(), CardDataError> {
+    let problems = validate_card_data(CARD_DATA_DIR);
+    for problem in problems.iter() {
+        println!("{problem}");
+    }
+
+    if !problems.is_empty() {
+        eprintln!("{} problem(s) found.", problems.len());
+        exit(1);
+    }
+    println!("All card data in {CARD_DATA_DIR} is valid.");
+
+    Ok(())
+}
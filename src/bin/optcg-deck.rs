@@ -0,0 +1,268 @@
+use std::process::exit;
+
+use rand::prelude::*;
+
+use optcg::card::*;
+use optcg::card_database::*;
+use optcg::card_query::*;
+use optcg::deck_formats::*;
+use optcg::deck_history::*;
+use optcg::deck_stats::*;
+use optcg::format::*;
+use optcg::probability::*;
+use optcg::utils::*;
+
+// Checks and converts deck lists. Any supported deck list format is accepted and detected.
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("check") if args.len() >= 2 => check(&args[1], option(&args, "--format")),
+        Some("convert") if args.len() >= 2 => match option(&args, "--to").map(|to| to.parse()) {
+            Some(Ok(to)) => convert(&args[1], to, option(&args, "--format")),
+            Some(Err(e)) => Err(e),
+            None => Err("missing `--to <format>`".to_string()),
+        },
+        Some("stats") if args.len() >= 2 => {
+            stats(&args[1], args.iter().any(|a| a == "--json"), option(&args, "--format"))
+        }
+        Some("odds") if args.len() >= 2 => odds(&args[1], &args[2..]),
+        Some("diff") if args.len() >= 2 => match option(&args, "--since") {
+            Some(revision) => diff_since(&args[1], revision),
+            None if args.len() >= 3 => diff(&args[1], &args[2]),
+            None => Err("missing a second deck file or `--since <revision>`".to_string()),
+        },
+        Some("save") if args.len() >= 3 => save(&args[1], &args[2]),
+        Some("history") if args.len() >= 2 => history(&args[1]),
+        Some("code") if args.len() >= 2 => code(&args[1], option(&args, "--format")),
+        Some("decode") if args.len() >= 2 => {
+            let to = option(&args, "--to").unwrap_or("optcg").parse();
+            to.and_then(|to| decode(&args[1], to, option(&args, "--format")))
+        }
+        _ => {
+            print_usage();
+            exit(2);
+        }
+    };
+
+    if let Err(e) = result {
+        eprintln!("{e}");
+        exit(1);
+    }
+}
+
+fn print_usage() {
+    eprintln!("usage: optcg-deck check <deck file> [--format <name>]");
+    eprintln!("       optcg-deck convert <deck file> --to <optcg|simulator|json> [--format <name>]");
+    eprintln!("       optcg-deck stats <deck file> [--json] [--format <name>]");
+    eprintln!("       optcg-deck odds <deck file> [filters] [--at-least <n>] [--turn <n>] [--draw] [--mulligan]");
+    eprintln!("                       [--trials <n>] [--format <name>]");
+    eprintln!("       optcg-deck diff <deck file> <deck file>");
+    eprintln!("       optcg-deck diff <deck file> --since <revision>");
+    eprintln!("       optcg-deck save <deck file> <revision>");
+    eprintln!("       optcg-deck history <deck file>");
+    eprintln!("       optcg-deck code <deck file> [--format <name>]");
+    eprintln!("       optcg-deck decode <deck code> [--to <optcg|simulator|json>] [--format <name>]");
+    eprintln!();
+    eprintln!("--format picks the rules from {FORMAT_DIR}, and defaults to standard.");
+    eprintln!("save keeps a named copy of the deck in <deck file>.history.json, for diff --since.");
+    eprintln!("odds counts the main deck cards matching every filter:");
+    eprintln!("{CARD_QUERY_USAGE}");
+}
+
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .map(|value| value.as_str())
+}
+
+// Loads everything a deck file needs, and reports every problem with it.
+fn load_deck(path: &str, format: Option<&str>) -> Result<(Card, Deck, Deck), String> {
+    let deck_list = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    let card_database = CardDatabase::load(CARD_DATA_DIR).map_err(|e| e.to_string())?;
+    let format = Format::load_named(FORMAT_DIR, format.unwrap_or("standard")).map_err(|e| e.to_string())?;
+
+    import_deck_list(&deck_list, &card_database, &format).map_err(|diagnostics| {
+        diagnostics
+            .iter()
+            .map(|d| format!("{path}:{d}"))
+            .collect::<Vec<String>>()
+            .join("\n")
+    })
+}
+
+fn check(path: &str, format: Option<&str>) -> Result<(), String> {
+    let (leader, main_deck, _) = load_deck(path, format)?;
+    println!("{path}: legal {} deck, {} main deck cards.", leader.name, main_deck.len());
+    Ok(())
+}
+
+fn convert(path: &str, to: DeckListFormat, format: Option<&str>) -> Result<(), String> {
+    let (leader, main_deck, don_deck) = load_deck(path, format)?;
+    println!("{}", export_deck_list(&leader, &main_deck, &don_deck, to));
+    Ok(())
+}
+
+fn stats(path: &str, json: bool, format: Option<&str>) -> Result<(), String> {
+    let (leader, main_deck, _) = load_deck(path, format)?;
+    let stats = DeckStats::new(&leader, &main_deck);
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats).unwrap());
+    } else {
+        print!("{stats}");
+    }
+    Ok(())
+}
+
+// e.g. `odds deck.txt --cost 2 --category character --turn 2 --draw --mulligan`.
+fn odds(path: &str, options: &[String]) -> Result<(), String> {
+    let mut query = CardQuery::default();
+    let mut question = HandQuestion::default();
+    let mut trials = 10000;
+    let mut format = None;
+
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        match option.as_str() {
+            "--draw" => question.going_first = false,
+            "--play" => question.going_first = true,
+            "--mulligan" => question.mulligan = true,
+            option => {
+                let value = options.next().ok_or(format!("missing value for `{option}`"))?;
+                if query.set_option(option, value)? {
+                    continue;
+                }
+                let number = || value.parse::<usize>().map_err(|_| format!("`{value}` is not a number"));
+                match option {
+                    "--at-least" => question.at_least = number()?,
+                    "--turn" => question.turn = number()? as u32,
+                    "--trials" => {
+                        trials = number()?;
+                        if trials == 0 {
+                            return Err("`--trials` needs at least 1 game".to_string());
+                        }
+                    }
+                    "--format" => format = Some(value.as_str()),
+                    _ => return Err(format!("unknown option `{option}`")),
+                }
+            }
+        }
+    }
+
+    let (leader, main_deck, _) = load_deck(path, format)?;
+    question.life = leader.life() as usize;
+
+    let matches = |card: &Card| query.matches(card);
+    let matching = main_deck.iter().filter(|card| matches(card)).count();
+    let exact = hand_probability(&main_deck, matches, &question);
+    let simulated = simulate_hand_probability(&main_deck, matches, &question, trials, &mut StdRng::from_entropy());
+
+    let play = if question.going_first { "on the play" } else { "on the draw" };
+    let mulligan = if question.mulligan { " with a mulligan" } else { "" };
+    println!(
+        "{matching} of {} main deck cards match. At least {} by turn {} {play}{mulligan}:",
+        main_deck.len(),
+        question.at_least,
+        question.turn,
+    );
+    println!("  exact      {:.2}%", exact * 100.0);
+    if let Some(simulated) = simulated {
+        println!("  simulated  {:.2}% over {trials} games", simulated * 100.0);
+    }
+    Ok(())
+}
+
+fn read_entries(path: &str) -> Result<Vec<DeckListEntry>, String> {
+    let deck_list = std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?;
+    read_deck_list_entries(&deck_list).map_err(|diagnostics| {
+        diagnostics
+            .iter()
+            .map(|d| format!("{path}:{d}"))
+            .collect::<Vec<String>>()
+            .join("\n")
+    })
+}
+
+fn print_diff(diff: &[CardDiff]) {
+    if diff.is_empty() {
+        println!("no changes");
+    }
+    for card in diff.iter() {
+        println!("{card}");
+    }
+}
+
+fn diff(before: &str, after: &str) -> Result<(), String> {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).map_err(|e| e.to_string())?;
+    print_diff(&diff_deck_list_entries(&read_entries(before)?, &read_entries(after)?, &card_database));
+    Ok(())
+}
+
+fn diff_since(path: &str, revision: &str) -> Result<(), String> {
+    let history = DeckHistory::load(&DeckHistory::path_for(path)).map_err(|e| e.to_string())?;
+    let Some(revision) = history.get(revision) else {
+        return Err(format!("{path} has no revision named `{revision}`"));
+    };
+    let card_database = CardDatabase::load(CARD_DATA_DIR).map_err(|e| e.to_string())?;
+    print_diff(&diff_deck_list_entries(&revision.entries, &read_entries(path)?, &card_database));
+    Ok(())
+}
+
+fn save(path: &str, revision: &str) -> Result<(), String> {
+    let history_path = DeckHistory::path_for(path);
+    let mut history = DeckHistory::load(&history_path).map_err(|e| e.to_string())?;
+    let entries = read_entries(path)?;
+    let card_database = CardDatabase::load(CARD_DATA_DIR).map_err(|e| e.to_string())?;
+
+    if let Some(latest) = history.latest() {
+        println!("changes since {}:", latest.name);
+        print_diff(&diff_deck_list_entries(&latest.entries, &entries, &card_database));
+    }
+    history.add(revision, entries)?;
+    history.save(&history_path).map_err(|e| e.to_string())?;
+    println!("saved {path} as {revision}.");
+    Ok(())
+}
+
+fn history(path: &str) -> Result<(), String> {
+    let history = DeckHistory::load(&DeckHistory::path_for(path)).map_err(|e| e.to_string())?;
+    let card_database = CardDatabase::load(CARD_DATA_DIR).map_err(|e| e.to_string())?;
+    for (i, revision) in history.revisions.iter().enumerate() {
+        let cards: i32 = revision.entries.iter().map(|e| e.quantity).sum();
+        let changes = match i {
+            0 => String::new(),
+            _ => {
+                let diff = diff_deck_list_entries(&history.revisions[i - 1].entries, &revision.entries, &card_database);
+                let added: i32 = diff.iter().map(|c| c.change().max(0)).sum();
+                let removed: i32 = diff.iter().map(|c| (-c.change()).max(0)).sum();
+                format!(", +{added} -{removed}")
+            }
+        };
+        println!("{}  {cards} cards{changes}", revision.name);
+    }
+    Ok(())
+}
+
+fn code(path: &str, format: Option<&str>) -> Result<(), String> {
+    let (leader, main_deck, don_deck) = load_deck(path, format)?;
+    println!("{}", encode_deck_code(&leader, &main_deck, &don_deck));
+    Ok(())
+}
+
+fn decode(code: &str, to: DeckListFormat, format: Option<&str>) -> Result<(), String> {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).map_err(|e| e.to_string())?;
+    let format = Format::load_named(FORMAT_DIR, format.unwrap_or("standard")).map_err(|e| e.to_string())?;
+
+    let (leader, main_deck, don_deck) = decode_deck_code(code, &card_database, &format).map_err(|diagnostics| {
+        diagnostics
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    })?;
+    println!("{}", export_deck_list(&leader, &main_deck, &don_deck, to));
+    Ok(())
+}
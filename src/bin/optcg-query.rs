@@ -0,0 +1,80 @@
+use std::process::exit;
+
+use optcg::card_database::*;
+use optcg::card_query::*;
+
+// Lists the cards in `assets/card_data` that match every given filter.
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let (query, search, json) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{e}");
+            print_usage();
+            exit(2);
+        }
+    };
+
+    let card_database = match CardDatabase::load(CARD_DATA_DIR) {
+        Ok(card_database) => card_database,
+        Err(e) => {
+            eprintln!("{e}");
+            exit(1);
+        }
+    };
+
+    let cards = match &search {
+        Some(text) => card_database
+            .search(text)
+            .into_iter()
+            .filter(|card| query.matches(card))
+            .collect(),
+        None => card_database.query(&query),
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&cards).unwrap());
+    } else {
+        for card in cards.iter() {
+            println!("{} {} ({})", card.identifier, card.name, card.art);
+            println!("{}", card);
+        }
+        println!("{} card(s) found.", cards.len());
+    }
+}
+
+fn print_usage() {
+    eprintln!("usage: optcg-query [filters] [--search <text>] [--json]");
+    eprintln!("{CARD_QUERY_USAGE}");
+    eprintln!("  --search <text>          words in the name, types or printed text");
+    eprintln!("  --json                   print the matching cards as JSON");
+}
+
+fn parse_args(args: &[String]) -> Result<(CardQuery, Option<String>, bool), String> {
+    let mut query = CardQuery::default();
+    let mut search = None;
+    let mut json = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--json" {
+            json = true;
+            continue;
+        }
+
+        let Some(value) = args.next() else {
+            return Err(format!("missing value for `{arg}`"));
+        };
+        if query.set_option(arg, value)? {
+            continue;
+        }
+        match arg.as_str() {
+            "--search" => search = Some(value.clone()),
+            _ => return Err(format!("unknown option `{arg}`")),
+        }
+    }
+
+    Ok((query, search, json))
+}
@@ -1,5 +1,5 @@
-use std::fs::File;
-use std::io::Read;
+use std::process::exit;
+use std::sync::Arc;
 
 use futures::prelude::*;
 use log::*;
@@ -9,9 +9,11 @@ use tokio::net::TcpListener;
 use tokio_serde::formats::*;
 use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
 
+use optcg::card::*;
+use optcg::card_database::*;
+use optcg::format::*;
 use optcg::game::*;
 use optcg::player::*;
-use optcg::utils::*;
 use optcg::*;
 
 #[tokio::main]
@@ -23,30 +25,20 @@ async fn main() -> std::io::Result<()> {
         ColorChoice::Auto,
     )
     .unwrap();
-    install_card_data();
 
-    let mut deck_list_file = File::open("sample_deck.txt").unwrap();
-    let mut deck_list = String::new();
-    deck_list_file.read_to_string(&mut deck_list).unwrap();
-
-    let (leader, main_deck, don_deck) = parse_deck_list(deck_list.as_str()).unwrap();
-
-    let player_1_default = Player {
-        name: "Player 1".into(),
-        leader: leader.clone(),
-        main_deck: main_deck.clone(),
-        don_deck: don_deck.clone(),
-        hand: vec![],
-        trash: vec![],
+    let card_database = match CardDatabase::load(CARD_DATA_DIR) {
+        Ok(card_database) => Arc::new(card_database),
+        Err(e) => {
+            error!("{e}");
+            exit(1);
+        }
     };
-
-    let player_2_default = Player {
-        name: "Player 2".into(),
-        leader: leader.clone(),
-        main_deck: main_deck.clone(),
-        don_deck: don_deck.clone(),
-        hand: vec![],
-        trash: vec![],
+    let format = match Format::load_named(FORMAT_DIR, "standard") {
+        Ok(format) => Arc::new(format),
+        Err(e) => {
+            error!("{e}");
+            exit(1);
+        }
     };
 
     let listener = TcpListener::bind("127.0.0.1:8080").await?;
@@ -55,8 +47,8 @@ async fn main() -> std::io::Result<()> {
         let (mut p1_socket, _) = listener.accept().await?;
         let (mut p2_socket, _) = listener.accept().await?;
 
-        let player_1 = player_1_default.clone();
-        let player_2 = player_2_default.clone();
+        let card_database = card_database.clone();
+        let format = format.clone();
 
         tokio::spawn(async move {
             let (p1_rx, p1_tx) = p1_socket.split();
@@ -72,7 +64,7 @@ async fn main() -> std::io::Result<()> {
             );
 
             let mut p1_client = PlayerClient {
-                player: Box::new(player_1.clone()),
+                player: Box::new(Player::empty()),
                 reader: p1_reader,
                 writer: p1_writer,
             };
@@ -90,11 +82,21 @@ async fn main() -> std::io::Result<()> {
             );
 
             let mut p2_client = PlayerClient {
-                player: Box::new(player_2.clone()),
+                player: Box::new(Player::empty()),
                 reader: p2_reader,
                 writer: p2_writer,
             };
 
+            // the game only starts once both players have sent a legal deck.
+            let (p1_deck, p2_deck) = tokio::join!(
+                p1_client.receive_deck(&card_database, &format),
+                p2_client.receive_deck(&card_database, &format),
+            );
+            let player_1 = new_player("Player 1", p1_deck);
+            let player_2 = new_player("Player 2", p2_deck);
+            *p1_client.player = player_1.clone();
+            *p2_client.player = player_2.clone();
+
             let mut playfield = GameState::setup(
                 player_1.clone(),
                 player_2.clone(),
@@ -108,9 +110,15 @@ async fn main() -> std::io::Result<()> {
                 p1_client.send_message(ServerMessage::Connected).await;
                 p2_client.send_message(ServerMessage::Connected).await;
 
-                let winner: Option<Turn> = playfield.check_loser(); // `Turn` is a unique representation of each player, so it works best here.
-                if let Some(winner) = winner {
+                let loser: Option<Turn> = playfield.check_loser(); // `Turn` is a unique representation of each player, so it works best here.
+                if let Some(loser) = loser {
+                    let winner = match loser {
+                        Turn::P1 => Turn::P2,
+                        Turn::P2 => Turn::P1,
+                    };
                     println!("Player {:?} wins!", winner);
+                    p1_client.send_message(ServerMessage::GameOver(winner)).await;
+                    p2_client.send_message(ServerMessage::GameOver(winner)).await;
                     break;
                 }
 
@@ -129,3 +137,14 @@ async fn main() -> std::io::Result<()> {
         });
     }
 }
+
+fn new_player(name: &str, (leader, main_deck, don_deck): (Card, Deck, Deck)) -> Player {
+    Player {
+        name: name.into(),
+        leader,
+        main_deck,
+        don_deck,
+        hand: vec![],
+        trash: vec![],
+    }
+}
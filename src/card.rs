@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use super::game::*;
+use super::{game::*, locale::*};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DeckError {
@@ -15,9 +15,45 @@ pub enum DeckError {
     InvalidCardArt(String),
     InvalidDeckLength(usize), // Requires exactly 61 cards total (Leader, Main deck, and DON!! deck)
     NotEnoughDon,             // Requires exactly 10 DON!! cards.
+    BannedCard(String),       // Banned in the format the deck is being built for.
+    RestrictedCard(String, i32, i32), // Identifier, copies in the deck and copies the format allows.
+    RotatedOut(String, i32),  // Identifier and block, which is older than the format allows.
+    ColorMismatch(String, String), // Identifier and name of a card that shares no color with the Leader.
+    InvalidQuantity(String),
+    UnreadableDeckList(String), // The deck list isn't valid in the format it was detected as.
+    InvalidDeckCode(String),
+    NotALeader(String),
+    LeaderInMainDeck(String), // A Leader added like any other card; it goes in with `DeckBuilder::set_leader`.
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+impl DeckError {
+    /// The card an error is about, for errors that are about one card.
+    pub fn identifier(&self) -> Option<&str> {
+        match self {
+            DeckError::TooManyCopies(id, _)
+            | DeckError::InvalidCardId(id)
+            | DeckError::BannedCard(id)
+            | DeckError::RestrictedCard(id, _, _)
+            | DeckError::RotatedOut(id, _)
+            | DeckError::ColorMismatch(id, _)
+            | DeckError::NotALeader(id)
+            | DeckError::LeaderInMainDeck(id) => Some(id),
+            _ => None,
+        }
+    }
+}
+
+/// A problem with a deck list, pointing at where it was found. Problems with the deck as a whole,
+/// like its length, have a `line` and `column` of 0.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckDiagnostic {
+    pub line: usize,   // 1 based.
+    pub column: usize, // 1 based.
+    pub text: String,  // The offending part of the line.
+    pub error: DeckError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CardColor {
     Red,
     Blue,
@@ -45,7 +81,7 @@ pub enum CardCategory {
     Don,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Attribute {
     Slash,   // SL
     Strike,  // ST
@@ -54,6 +90,19 @@ pub enum Attribute {
     Wisdom,  // WS
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Rarity {
+    Common,       // C
+    Uncommon,     // UC
+    Rare,         // R
+    SuperRare,    // SR
+    SecretRare,   // SEC
+    Leader,       // L
+    Special,      // SP
+    TreasureRare, // TR
+    Promo,        // P
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Facing {
     FaceUp,
@@ -64,6 +113,7 @@ pub enum Facing {
 pub enum Status {
     PowerPlus(i32),
     CostMinus(i32),
+    Granted(Keyword),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,9 +129,17 @@ pub struct Card {
     pub color: Vec<CardColor>,     // Some cards have more than one color.
     pub types: Vec<String>,        // Some cards have more than one type.
     pub effects: Vec<Effect>,
+    pub text: String, // Printed card text, which should match `card_text::card_text` for the effects.
+    pub set: String,      // Set code printed before the card number, e.g. `ST01` or `OP09`. Empty for DON!! cards.
+    pub set_name: String, // e.g. `Straw Hat Crew`.
+    pub rarity: Option<Rarity>, // DON!! cards have no rarity.
+    pub block: i32,       // Block number printed on the card, used for rotation. 0 when unknown.
+    pub release_date: String, // English release date of the set, as `YYYY-MM-DD`.
+    pub translations: CardTranslations, // Printed name and text in other languages.
     pub attached_don: Deck, // Only Leader and Character cards can have a don attached
-    pub status: Vec<Status>,
+    pub status: Vec<Status>, // Results of continuous effects, rebuilt by `PlayerArea::recompute_static_effects`.
     pub facing: Facing,
+    pub played_this_turn: bool, // Characters can't attack the turn they're played, unless they have [Rush].
 }
 
 impl Card {
@@ -111,9 +169,17 @@ impl Card {
             color,
             types,
             effects,
+            text: String::new(),
+            set: String::new(),
+            set_name: String::new(),
+            rarity: None,
+            block: 0,
+            release_date: String::new(),
+            translations: CardTranslations::new(),
             attached_don: vec![],
             status: vec![],
             facing,
+            played_this_turn: false,
         }
     }
 
@@ -131,6 +197,10 @@ impl Card {
         }
     }
 
+    pub fn is_category(&self, category: CardCategory) -> bool {
+        std::mem::discriminant(&self.category) == std::mem::discriminant(&category)
+    }
+
     pub fn is_don(&self) -> bool {
         match self.category {
             CardCategory::Don => true,
@@ -138,6 +208,119 @@ impl Card {
         }
     }
 
+    /// Whether this card has `keyword`, either printed on it or granted by a continuous effect.
+    pub fn has_keyword(&self, keyword: Keyword) -> bool {
+        self.effects.iter().any(|effect| effect.keyword() == Some(keyword))
+            || self
+                .status
+                .iter()
+                .any(|status| matches!(status, Status::Granted(k) if *k == keyword))
+    }
+
+    pub fn current_power(&self) -> Option<CardPower> {
+        let modifier: i32 = self
+            .status
+            .iter()
+            .map(|status| match status {
+                Status::PowerPlus(n) => *n,
+                _ => 0,
+            })
+            .sum();
+        self.power.map(|power| CardPower(power.0 + modifier))
+    }
+
+    pub fn current_cost(&self) -> CardCost {
+        let modifier: i32 = self
+            .status
+            .iter()
+            .map(|status| match status {
+                Status::CostMinus(n) => *n,
+                _ => 0,
+            })
+            .sum();
+        CardCost((self.cost.0 - modifier).max(0))
+    }
+
+    /// The effects of this card's continuous effects that currently apply. `owners_turn` selects
+    /// between `DuringTurn` and `DuringOpponentsTurn` effects.
+    pub fn active_static_effects(&self, owners_turn: bool) -> Vec<&Effect> {
+        let mut active_effects = vec![];
+
+        for effect in self.effects.iter() {
+            let Effect::TimedEffect(timing, cost, effects) = effect else {
+                continue;
+            };
+            let active = match timing {
+                Timing::Always => true,
+                Timing::DuringTurn => owners_turn,
+                Timing::DuringOpponentsTurn => !owners_turn,
+                _ => false,
+            };
+            let requirement_met = match cost {
+                EffectCost::DonAttached(n) => self.attached_don.len() as i32 >= *n,
+                EffectCost::Zero => true,
+                _ => false, // continuous effects cannot be paid for.
+            };
+            if active && requirement_met {
+                active_effects.extend(effects.iter());
+            }
+        }
+
+        active_effects
+    }
+
+    /// Rebuilds `status` from this card's own continuous effects and the DON!! attached to it.
+    /// Effects that reach other cards, like `YourCharacters`, are added by `PlayerArea::recompute_static_effects`.
+    pub fn recompute_static_effects(&mut self, owners_turn: bool) {
+        let mut status = statuses(self.active_static_effects(owners_turn));
+
+        // each attached DON!! card gives its own power bonus during the owner's turn.
+        if owners_turn {
+            for don in self.attached_don.iter() {
+                for effect in don.effects.iter() {
+                    if let Effect::PlusPower(n) = effect {
+                        status.push(Status::PowerPlus(*n));
+                    }
+                }
+            }
+        }
+
+        self.status = status;
+    }
+
+    pub fn has_trigger(&self) -> bool {
+        self.effects
+            .iter()
+            .any(|effect| matches!(effect, Effect::TimedEffect(Timing::Trigger, _, _)))
+    }
+
+    /// The effects of this card's [Trigger], if it has one.
+    pub fn trigger_effects(&self) -> Option<&Vec<Effect>> {
+        self.effects.iter().find_map(|effect| match effect {
+            Effect::TimedEffect(Timing::Trigger, _, effects) => Some(effects),
+            _ => None,
+        })
+    }
+
+    /// Whether this Character is ready to attack. The Leader and rested Characters are checked separately.
+    pub fn can_attack(&self) -> bool {
+        !self.played_this_turn || self.has_keyword(Keyword::Rush)
+    }
+
+    /// Number of life cards this card removes when it deals damage to a Leader.
+    pub fn damage(&self) -> i32 {
+        if self.has_keyword(Keyword::DoubleAttack) {
+            2
+        } else {
+            1
+        }
+    }
+
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+
     pub fn set_faceup(mut self) -> Self {
         self.facing = Facing::FaceUp;
         self
@@ -175,3 +358,20 @@ impl SetFacing for Deck {
         set
     }
 }
+
+/// What continuous `effects` do to the card they apply to.
+pub fn statuses<'a>(effects: impl IntoIterator<Item = &'a Effect>) -> Vec<Status> {
+    let mut status = vec![];
+    for effect in effects {
+        match effect {
+            Effect::PlusPower(n) => status.push(Status::PowerPlus(*n)),
+            Effect::MinusCost(n) => status.push(Status::CostMinus(*n)),
+            effect => {
+                if let Some(keyword) = effect.keyword() {
+                    status.push(Status::Granted(keyword));
+                }
+            }
+        }
+    }
+    status
+}
@@ -0,0 +1,174 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::{card::*, card_definition::*, utils::*};
+
+pub const CARD_DATA_DIR: &str = "assets/card_data";
+
+type CardKey = (String, String); // Identifier and art.
+
+/// Every card definition in a card data directory, loaded once and indexed by identifier and art,
+/// as well as by set, rarity, block and the words in its name, types and text.
+#[derive(Debug, Clone, Default)]
+pub struct CardDatabase {
+    cards: BTreeMap<String, BTreeMap<String, Card>>,
+    base_arts: BTreeMap<String, String>, // The art a card is listed with first.
+    sets: BTreeMap<String, BTreeSet<CardKey>>,
+    rarities: BTreeMap<Rarity, BTreeSet<CardKey>>,
+    blocks: BTreeMap<i32, BTreeSet<CardKey>>,
+    words: BTreeMap<String, BTreeSet<CardKey>>,
+}
+
+impl CardDatabase {
+    pub fn load(dir: &str) -> Result<CardDatabase, CardDataError> {
+        let mut database = CardDatabase::default();
+
+        for (file, definition) in load_card_definitions(dir)? {
+            let cards = definition
+                .to_cards()
+                .map_err(|e| CardDataError::InFile(file.clone(), Box::new(CardDataError::Script(e))))?;
+            for card in cards {
+                database.insert(card).map_err(|e| CardDataError::InFile(file.clone(), Box::new(e)))?;
+            }
+        }
+
+        Ok(database)
+    }
+
+    pub fn load_default() -> Result<CardDatabase, CardDataError> {
+        Self::load(CARD_DATA_DIR)
+    }
+
+    /// Adds one art of a card. The first art added for an identifier is its base art.
+    pub fn insert(&mut self, card: Card) -> Result<(), CardDataError> {
+        let arts = self.cards.entry(card.identifier.clone()).or_default();
+        if arts.contains_key(&card.art) {
+            return Err(CardDataError::DuplicateCard(card.identifier, card.art));
+        }
+        self.base_arts.entry(card.identifier.clone()).or_insert(card.art.clone());
+
+        let key = (card.identifier.clone(), card.art.clone());
+        // DON!! cards belong to no set.
+        if !card.set.is_empty() {
+            self.sets.entry(card.set.clone()).or_default().insert(key.clone());
+        }
+        if let Some(rarity) = card.rarity {
+            self.rarities.entry(rarity).or_default().insert(key.clone());
+        }
+        self.blocks.entry(card.block).or_default().insert(key.clone());
+
+        let mut searchable = [card.name.as_str(), &card.types.join(" "), card.text.as_str()].join(" ");
+        for translation in card.translations.values() {
+            searchable = [searchable.as_str(), &translation.name, &translation.text].join(" ");
+        }
+        for word in search_words(&searchable) {
+            self.words.entry(word).or_default().insert(key.clone());
+        }
+
+        arts.insert(card.art.clone(), card);
+        Ok(())
+    }
+
+    /// Looks up a card by identifier and art. An empty `art` picks the card's base art, which is
+    /// how deck lists refer to a card without naming an art.
+    pub fn get(&self, identifier: &str, art: &str) -> Result<&Card, DeckError> {
+        let Some(arts) = self.cards.get(identifier) else {
+            return Err(DeckError::InvalidCardId(identifier.to_string()));
+        };
+
+        let card = if art.is_empty() {
+            self.base_arts.get(identifier).and_then(|art| arts.get(art))
+        } else {
+            arts.get(art)
+        };
+
+        card.ok_or_else(|| DeckError::InvalidCardArt(format!("{identifier} ({art})")))
+    }
+
+    pub fn arts(&self, identifier: &str) -> Vec<&Card> {
+        match self.cards.get(identifier) {
+            Some(arts) => arts.values().collect(),
+            None => vec![],
+        }
+    }
+
+    /// Every card, ordered by identifier and then art.
+    pub fn cards(&self) -> impl Iterator<Item = &Card> {
+        self.cards.values().flat_map(|arts| arts.values())
+    }
+
+    /// Every set code with at least one card, in order.
+    pub fn sets(&self) -> impl Iterator<Item = &str> {
+        self.sets.keys().map(|set| set.as_str())
+    }
+
+    pub fn in_set(&self, set: &str) -> Vec<&Card> {
+        self.indexed(self.sets.get(set))
+    }
+
+    pub fn with_rarity(&self, rarity: Rarity) -> Vec<&Card> {
+        self.indexed(self.rarities.get(&rarity))
+    }
+
+    pub fn in_block(&self, block: i32) -> Vec<&Card> {
+        self.indexed(self.blocks.get(&block))
+    }
+
+    /// Cards whose name, types or text contain every word of `text`. The last word may be
+    /// unfinished, so `straw h` already finds Straw Hat Crew cards.
+    pub fn search(&self, text: &str) -> Vec<&Card> {
+        let words = search_words(text);
+        let Some((last, whole)) = words.split_last() else {
+            return vec![];
+        };
+
+        let mut found: Option<BTreeSet<CardKey>> = None;
+        let mut narrow = |keys: BTreeSet<CardKey>| {
+            found = Some(match found.take() {
+                Some(found) => found.intersection(&keys).cloned().collect(),
+                None => keys,
+            });
+        };
+
+        for word in whole.iter() {
+            narrow(self.words.get(word).cloned().unwrap_or_default());
+        }
+        narrow(
+            self.words
+                .range(last.clone()..)
+                .take_while(|(word, _)| word.starts_with(last.as_str()))
+                .flat_map(|(_, keys)| keys.iter().cloned())
+                .collect(),
+        );
+
+        self.indexed(found.as_ref())
+    }
+
+    fn indexed(&self, keys: Option<&BTreeSet<CardKey>>) -> Vec<&Card> {
+        keys.into_iter()
+            .flatten()
+            .filter_map(|(identifier, art)| self.cards.get(identifier)?.get(art))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.values().map(|arts| arts.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+}
+
+// Lowercase words, split on anything that isn't a letter or digit, so `K.O.` and `DON!!` are
+// found by searching for `ko` and `don`.
+fn search_words(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || c == '-' || c == '/')
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(|c| c.to_lowercase())
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
@@ -0,0 +1,318 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{card::*, game::*, locale::*, script::*, utils::*};
+
+// Version history of the on-disk card schema:
+//   0 - a direct serde dump of `Card`, including runtime fields like `attached_don` and `facing`.
+//   1 - `CardDefinition`, with a `schema_version` and no runtime fields.
+//   2 - adds `set`, `set_name`, `rarity`, `block` and `release_date`.
+//   3 - `art` becomes `arts`, every art of a card in one file named after its identifier.
+pub const CARD_SCHEMA_VERSION: u64 = 3;
+
+/// How a card is stored in `assets/card_data`. Unlike `Card`, this holds no in-game state,
+/// so the game can grow new runtime fields without touching any card data. Alternate arts share
+/// the definition, and the first art listed is the base art.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CardDefinition {
+    pub schema_version: u64,
+    pub name: String,
+    pub identifier: String,
+    pub arts: Vec<String>,
+    pub cost: CardCost,
+    pub category: CardCategory,
+    pub power: Option<CardPower>,
+    pub counter_power: Option<CounterPower>,
+    #[serde(default)]
+    pub attribute: Vec<Attribute>,
+    #[serde(default)]
+    pub color: Vec<CardColor>,
+    #[serde(default)]
+    pub types: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub effects: Vec<Effect>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>, // Replaces `effects` when present.
+    #[serde(default)]
+    pub text: String,
+    pub set: String,
+    pub set_name: String,
+    pub rarity: Option<Rarity>,
+    pub block: i32,
+    pub release_date: String,
+    #[serde(default, skip_serializing_if = "CardTranslations::is_empty")]
+    pub translations: CardTranslations,
+}
+
+impl CardDefinition {
+    pub fn from_card(card: &Card) -> CardDefinition {
+        CardDefinition {
+            schema_version: CARD_SCHEMA_VERSION,
+            name: card.name.clone(),
+            identifier: card.identifier.clone(),
+            arts: vec![card.art.clone()],
+            cost: card.cost,
+            category: card.category,
+            power: card.power,
+            counter_power: card.counter_power,
+            attribute: card.attribute.clone(),
+            color: card.color.clone(),
+            types: card.types.clone(),
+            effects: card.effects.clone(),
+            script: None,
+            text: card.text.clone(),
+            set: card.set.clone(),
+            set_name: card.set_name.clone(),
+            rarity: card.rarity,
+            block: card.block,
+            release_date: card.release_date.clone(),
+            translations: card.translations.clone(),
+        }
+    }
+
+    /// A fresh, face down game instance of this card in its base art.
+    pub fn to_card(&self) -> Result<Card, ScriptError> {
+        let art = self.arts.first().cloned().unwrap_or_default();
+        self.to_card_with_art(&art)
+    }
+
+    /// One game instance of this card for every art, base art first.
+    pub fn to_cards(&self) -> Result<Vec<Card>, ScriptError> {
+        self.arts.iter().map(|art| self.to_card_with_art(art)).collect()
+    }
+
+    fn to_card_with_art(&self, art: &str) -> Result<Card, ScriptError> {
+        let effects = match &self.script {
+            Some(script) => parse_script(script)?,
+            None => self.effects.clone(),
+        };
+
+        let mut card = Card::new(
+            self.name.clone(),
+            self.identifier.clone(),
+            art.to_string(),
+            self.cost,
+            self.category,
+            self.power,
+            self.counter_power,
+            self.attribute.clone(),
+            self.color.clone(),
+            self.types.clone(),
+            effects,
+            Facing::FaceDown,
+        )
+        .with_text(&self.text);
+        card.set = self.set.clone();
+        card.set_name = self.set_name.clone();
+        card.rarity = self.rarity;
+        card.block = self.block;
+        card.release_date = self.release_date.clone();
+        card.translations = self.translations.clone();
+
+        Ok(card)
+    }
+}
+
+pub fn schema_version(card_data: &Value) -> u64 {
+    card_data
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+}
+
+/// Upgrades card data written with any older schema to `CARD_SCHEMA_VERSION`, one version at a time.
+pub fn migrate(mut card_data: Value) -> Result<Value, CardDataError> {
+    let version = schema_version(&card_data);
+    if version > CARD_SCHEMA_VERSION {
+        return Err(CardDataError::UnsupportedSchemaVersion(version));
+    }
+
+    let Some(fields) = card_data.as_object_mut() else {
+        return Err(CardDataError::Json("card data must be a JSON object".into()));
+    };
+
+    if version < 1 {
+        fields.remove("attached_don");
+        fields.remove("status");
+        fields.remove("facing");
+        fields.insert("schema_version".into(), Value::from(1));
+    }
+
+    if version < 2 {
+        // the set code is the part of the identifier before the card number; DON!! cards have none.
+        let identifier = fields.get("identifier").and_then(|v| v.as_str()).unwrap_or_default();
+        let set = match identifier.split_once('-') {
+            Some((set, _)) if fields.get("category") != Some(&Value::from("Don")) => set.to_string(),
+            _ => String::new(),
+        };
+        fields.insert("set".into(), Value::from(set));
+        fields.insert("set_name".into(), Value::from(""));
+        fields.insert("rarity".into(), Value::Null);
+        fields.insert("block".into(), Value::from(0));
+        fields.insert("release_date".into(), Value::from(""));
+        fields.insert("schema_version".into(), Value::from(2));
+    }
+
+    if version < 3 {
+        // `migrate_card_files` merges the files of a card's other arts into this one.
+        let art = fields.remove("art").unwrap_or(Value::from("P0"));
+        fields.insert("arts".into(), Value::Array(vec![art]));
+        fields.insert("schema_version".into(), Value::from(3));
+    }
+
+    Ok(card_data)
+}
+
+pub fn load_card_definition(card_data: &str) -> Result<CardDefinition, CardDataError> {
+    let card_data: Value =
+        serde_json::from_str(card_data).map_err(|e| CardDataError::Json(e.to_string()))?;
+
+    serde_json::from_value(migrate(card_data)?).map_err(|e| CardDataError::Json(e.to_string()))
+}
+
+/// The file a card is stored in, `{identifier}.json`, which holds all of its arts.
+pub fn card_file_name(definition: &CardDefinition) -> String {
+    format!("{}.json", definition.identifier)
+}
+
+/// Every card definition in `dir`, paired with the file it was loaded from.
+pub fn load_card_definitions(dir: &str) -> Result<Vec<(String, CardDefinition)>, CardDataError> {
+    let in_file = |file: &str, e: CardDataError| CardDataError::InFile(file.to_string(), Box::new(e));
+
+    let entries = std::fs::read_dir(dir).map_err(|e| in_file(dir, CardDataError::Io(e.to_string())))?;
+    let mut definitions = vec![];
+
+    for entry in entries {
+        let path = entry
+            .map_err(|e| in_file(dir, CardDataError::Io(e.to_string())))?
+            .path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let file = path.display().to_string();
+
+        let definition = std::fs::read_to_string(&path)
+            .map_err(|e| CardDataError::Io(e.to_string()))
+            .and_then(|card_data| load_card_definition(&card_data))
+            .map_err(|e| in_file(&file, e))?;
+
+        definitions.push((file, definition));
+    }
+
+    definitions.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(definitions)
+}
+
+/// Writes `definition` into `dir`, refusing to replace an existing file unless `overwrite` is set.
+/// Returns the path that was written.
+pub fn write_card_file(dir: &str, definition: &CardDefinition, overwrite: bool) -> Result<String, CardDataError> {
+    let path = format!("{}/{}", dir, card_file_name(definition));
+    if !overwrite && std::path::Path::new(&path).exists() {
+        return Err(CardDataError::InFile(
+            path,
+            Box::new(CardDataError::Io("file already exists".into())),
+        ));
+    }
+
+    let card_data = serde_json::to_string_pretty(definition).map_err(|e| CardDataError::Json(e.to_string()))?;
+    std::fs::write(&path, card_data)
+        .map_err(|e| CardDataError::InFile(path.clone(), Box::new(CardDataError::Io(e.to_string()))))?;
+
+    Ok(path)
+}
+
+/// Rewrites every card file in `dir` that uses an older schema, merging the files that older
+/// schemas kept for each art of a card into one. Returns the files that were written.
+pub fn migrate_card_files(dir: &str) -> Result<Vec<String>, CardDataError> {
+    let in_file = |file: &str, e: CardDataError| CardDataError::InFile(file.to_string(), Box::new(e));
+
+    let entries = std::fs::read_dir(dir).map_err(|e| in_file(dir, CardDataError::Io(e.to_string())))?;
+    let mut files = vec![];
+    for entry in entries {
+        let path = entry
+            .map_err(|e| in_file(dir, CardDataError::Io(e.to_string())))?
+            .path();
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            files.push(path);
+        }
+    }
+
+    let mut definitions = vec![];
+    for path in files {
+        let file = path.display().to_string();
+
+        let card_data = std::fs::read_to_string(&path).map_err(|e| in_file(&file, CardDataError::Io(e.to_string())))?;
+        let card_data: Value =
+            serde_json::from_str(&card_data).map_err(|e| in_file(&file, CardDataError::Json(e.to_string())))?;
+        let outdated = schema_version(&card_data) != CARD_SCHEMA_VERSION;
+
+        let definition: CardDefinition = migrate(card_data)
+            .and_then(|card_data| {
+                serde_json::from_value(card_data).map_err(|e| CardDataError::Json(e.to_string()))
+            })
+            .map_err(|e| in_file(&file, e))?;
+        definitions.push((path, definition, outdated));
+    }
+    // the first file of a card keeps its base art: a file already on the current schema, then
+    // the P0 art, then the rest by name. Sorting by name alone would put `-P1.json` before `.json`.
+    definitions.sort_by_key(|(path, definition, outdated)| {
+        (*outdated, definition.arts.first().map(String::as_str) != Some("P0"), path.clone())
+    });
+
+    // each card, the files it was read from, and whether it needs writing.
+    let mut cards: Vec<(CardDefinition, Vec<std::path::PathBuf>, bool)> = vec![];
+    for (path, definition, outdated) in definitions {
+        match cards.iter_mut().find(|(card, _, _)| card.identifier == definition.identifier) {
+            Some((card, paths, rewrite)) => {
+                // only the arts are merged, so the files have to agree on everything printed the same on every art.
+                let conflict = if card.name != definition.name {
+                    Some("name")
+                } else if card.cost.0 != definition.cost.0 {
+                    Some("cost")
+                } else if card.text != definition.text {
+                    Some("text")
+                } else {
+                    None
+                };
+                if let Some(field) = conflict {
+                    let conflict = CardDataError::ConflictingArts(definition.identifier.clone(), field.to_string());
+                    return Err(in_file(&path.display().to_string(), conflict));
+                }
+
+                for art in definition.arts {
+                    if !card.arts.contains(&art) {
+                        card.arts.push(art);
+                    }
+                }
+                paths.push(path);
+                *rewrite = true;
+            }
+            None => {
+                let misnamed = path.file_name().and_then(|name| name.to_str()) != Some(&card_file_name(&definition));
+                cards.push((definition, vec![path], outdated || misnamed));
+            }
+        }
+    }
+
+    let mut upgraded = vec![];
+    for (definition, paths, rewrite) in cards.iter() {
+        if !rewrite {
+            continue;
+        }
+
+        // round trip through `CardDefinition` so the file ends up in the canonical field order.
+        let path = std::path::Path::new(dir).join(card_file_name(definition));
+        let file = path.display().to_string();
+        let card_data = serde_json::to_string_pretty(definition).unwrap();
+        std::fs::write(&path, card_data).map_err(|e| in_file(&file, CardDataError::Io(e.to_string())))?;
+
+        for old in paths.iter().filter(|old| **old != path) {
+            std::fs::remove_file(old)
+                .map_err(|e| in_file(&old.display().to_string(), CardDataError::Io(e.to_string())))?;
+        }
+
+        upgraded.push(file);
+    }
+
+    Ok(upgraded)
+}
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use super::{card::*, card_definition::*, card_text::*, card_types::*, card_validation::*, utils::*};
+
+// Reads card lists exported from a spreadsheet as CSV or TSV. The first row names the columns;
+// the required ones are
+//   id, art, name, cost, category, power, counter, attributes, colors, types, life, effect script
+// and `text`, `set name`, `rarity`, `block` and `release date` may be added. Lists inside a cell
+// are separated by `/` like on the printed card, e.g. `Animal/Straw Hat Crew`, and an effect
+// script cell holds one effect per line. Each art of a card is a row of its own, and rows after
+// the first only add their art to the card, so every other column has to match the first row.
+
+const REQUIRED_COLUMNS: &[&str] = &[
+    "id",
+    "art",
+    "name",
+    "cost",
+    "category",
+    "power",
+    "counter",
+    "attributes",
+    "colors",
+    "types",
+    "life",
+    "effect script",
+];
+
+#[derive(Debug, Clone)]
+pub struct RowError {
+    pub row: usize, // 1-based, counting the header row, so it matches the spreadsheet.
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}: {}", self.row, self.message)
+    }
+}
+
+/// Tab separated if the header row has a tab in it, otherwise comma separated.
+pub fn detect_delimiter(card_list: &str) -> char {
+    match card_list.lines().next() {
+        Some(header) if header.contains('\t') => '\t',
+        _ => ',',
+    }
+}
+
+/// Every card in a CSV or TSV card list, checked with the same rules as `optcg-cards validate`.
+/// Any row with a problem is left out and reported, so the caller can decide whether to
+/// write the rest.
+pub fn import_card_list(card_list: &str, delimiter: char) -> (Vec<CardDefinition>, Vec<RowError>) {
+    let rows = match split_rows(card_list, delimiter) {
+        Ok(rows) => rows,
+        Err(e) => return (vec![], vec![e]),
+    };
+    let Some((header, rows)) = rows.split_first() else {
+        return (vec![], vec![RowError { row: 1, message: "missing header row".to_string() }]);
+    };
+
+    let columns: HashMap<String, usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (column_key(name), i))
+        .collect();
+    let missing: Vec<&str> = REQUIRED_COLUMNS
+        .iter()
+        .copied()
+        .filter(|name| !columns.contains_key(&column_key(name)))
+        .collect();
+    if !missing.is_empty() {
+        let message = format!("missing column(s): {}", missing.join(", "));
+        return (vec![], vec![RowError { row: 1, message }]);
+    }
+
+    let mut definitions: Vec<CardDefinition> = vec![];
+    let mut errors = vec![];
+
+    for (i, cells) in rows.iter().enumerate() {
+        let row = i + 2;
+        if cells.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+
+        let cell = |name: &str| -> &str {
+            columns
+                .get(&column_key(name))
+                .and_then(|i| cells.get(*i))
+                .map(|cell| cell.trim())
+                .unwrap_or_default()
+        };
+
+        match card_definition_from_row(&cell) {
+            Ok(definition) => {
+                let problems = validate_card_definition(&definition);
+                if !problems.is_empty() {
+                    errors.extend(problems.into_iter().map(|message| RowError { row, message }));
+                    continue;
+                }
+                // another art of a card from an earlier row.
+                match definitions.iter_mut().find(|d| d.identifier == definition.identifier) {
+                    Some(card) if card.arts.contains(&definition.arts[0]) => {
+                        let message = format!("{} ({}) is listed more than once", card.identifier, definition.arts[0]);
+                        errors.push(RowError { row, message });
+                    }
+                    Some(card) => match conflicting_field(card, &definition) {
+                        Some(field) => {
+                            let message = CardDataError::ConflictingArts(definition.identifier, field).to_string();
+                            errors.push(RowError { row, message });
+                        }
+                        None => card.arts.extend(definition.arts),
+                    },
+                    None => definitions.push(definition),
+                }
+            }
+            Err(message) => errors.push(RowError { row, message }),
+        }
+    }
+
+    (definitions, errors)
+}
+
+// The first field other than the arts that two definitions of the same card disagree on.
+fn conflicting_field(card: &CardDefinition, other: &CardDefinition) -> Option<String> {
+    let fields = |definition: &CardDefinition| {
+        let mut fields = serde_json::to_value(definition).unwrap();
+        fields.as_object_mut().unwrap().remove("arts");
+        fields
+    };
+    let (card, other) = (fields(card), fields(other));
+
+    card.as_object()
+        .unwrap()
+        .iter()
+        .find(|(field, value)| other.get(field.as_str()) != Some(*value))
+        .map(|(field, _)| field.replace('_', " "))
+}
+
+fn card_definition_from_row<'a>(cell: &dyn Fn(&str) -> &'a str) -> Result<CardDefinition, String> {
+    let identifier = cell("id").to_string();
+    if identifier.is_empty() {
+        return Err("missing id".to_string());
+    }
+
+    let mut category: CardCategory = cell("category").parse()?;
+    if let CardCategory::Leader(_) = category {
+        category = CardCategory::Leader(parse_number("life", cell("life"))?.unwrap_or(0));
+    }
+
+    let mut types = vec![];
+    for card_type in split_list(cell("types")) {
+        match normalise_type(card_type) {
+            Some(t) => types.push(t.to_string()),
+            None => return Err(format!("unknown type `{card_type}`")),
+        }
+    }
+
+    let script = cell("effect script");
+    let set = match identifier.split_once('-') {
+        Some((set, _)) if !matches!(category, CardCategory::Don) => set.to_string(),
+        _ => String::new(),
+    };
+    let rarity = match cell("rarity") {
+        "" => None,
+        rarity => Some(rarity.parse()?),
+    };
+
+    let mut definition = CardDefinition {
+        schema_version: CARD_SCHEMA_VERSION,
+        name: cell("name").to_string(),
+        identifier,
+        arts: match cell("art") {
+            "" => vec!["P0".to_string()],
+            art => vec![art.to_string()],
+        },
+        cost: CardCost(parse_number("cost", cell("cost"))?.unwrap_or(0)),
+        category,
+        power: parse_number("power", cell("power"))?.map(CardPower),
+        counter_power: parse_number("counter", cell("counter"))?.map(CounterPower),
+        attribute: split_list(cell("attributes"))
+            .map(|a| a.parse())
+            .collect::<Result<_, _>>()?,
+        color: split_list(cell("colors"))
+            .map(|c| c.parse())
+            .collect::<Result<_, _>>()?,
+        types,
+        effects: vec![],
+        script: (!script.is_empty()).then(|| script.to_string()),
+        text: cell("text").to_string(),
+        set,
+        set_name: cell("set name").to_string(),
+        rarity,
+        block: parse_number("block", cell("block"))?.unwrap_or(0),
+        release_date: cell("release date").to_string(),
+        translations: Default::default(),
+    };
+
+    // without a text column, the printed text is generated from the effects.
+    if definition.text.is_empty() {
+        let card = definition.to_card().map_err(|e| format!("effect script {e}"))?;
+        definition.text = card_text(&card);
+    }
+
+    Ok(definition)
+}
+
+fn parse_number(column: &str, cell: &str) -> Result<Option<i32>, String> {
+    match cell {
+        "" | "-" => Ok(None),
+        n => n
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("{column} `{n}` is not a number")),
+    }
+}
+
+fn split_list(cell: &str) -> impl Iterator<Item = &str> {
+    cell.split('/').map(|item| item.trim()).filter(|item| !item.is_empty())
+}
+
+fn column_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+// Splits a spreadsheet export into rows of cells. Cells may be wrapped in double quotes to hold
+// delimiters or line breaks, with `""` standing for a quote inside a quoted cell.
+fn split_rows(card_list: &str, delimiter: char) -> Result<Vec<Vec<String>>, RowError> {
+    let mut rows = vec![];
+    let mut row = vec![];
+    let mut cell = String::new();
+    let mut quoted = false;
+
+    let mut chars = card_list.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    cell.push('"');
+                }
+                '"' => quoted = false,
+                '\r' => {}
+                c => cell.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if cell.is_empty() => quoted = true,
+            '\r' => {}
+            '\n' => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            c if c == delimiter => row.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(RowError {
+            row: rows.len() + 1,
+            message: "quoted cell is never closed".to_string(),
+        });
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+
+    Ok(rows)
+}
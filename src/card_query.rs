@@ -0,0 +1,167 @@
+use super::{card::*, card_database::*, game::*};
+
+/// A filter over cards. Every field that is set must match; unset fields match anything.
+#[derive(Debug, Clone, Default)]
+pub struct CardQuery {
+    pub color: Option<CardColor>,
+    pub category: Option<CardCategory>,
+    pub min_cost: Option<i32>,
+    pub max_cost: Option<i32>,
+    pub min_power: Option<i32>,
+    pub max_power: Option<i32>,
+    pub types: Option<String>, // Case insensitive substring of any of the card's types.
+    pub attribute: Option<Attribute>,
+    pub keyword: Option<Keyword>, // Anywhere in the card's effects, including ones that need DON!!.
+    pub set: Option<String>,      // Exact set code, case insensitive.
+    pub rarity: Option<Rarity>,
+    pub min_block: Option<i32>,
+    pub max_block: Option<i32>,
+    pub released_by: Option<String>, // Only cards released on or before this `YYYY-MM-DD` date.
+}
+
+impl CardQuery {
+    pub fn matches(&self, card: &Card) -> bool {
+        if let Some(color) = self.color {
+            if !card.color.contains(&color) {
+                return false;
+            }
+        }
+
+        if let Some(category) = self.category {
+            if !card.is_category(category) {
+                return false;
+            }
+        }
+
+        if self.min_cost.is_some_and(|n| card.cost.0 < n)
+            || self.max_cost.is_some_and(|n| card.cost.0 > n)
+        {
+            return false;
+        }
+
+        if self.min_power.is_some() || self.max_power.is_some() {
+            let Some(power) = card.power else {
+                return false;
+            };
+            if self.min_power.is_some_and(|n| power.0 < n)
+                || self.max_power.is_some_and(|n| power.0 > n)
+            {
+                return false;
+            }
+        }
+
+        if let Some(types) = &self.types {
+            let types = types.to_lowercase();
+            if !card.types.iter().any(|t| t.to_lowercase().contains(&types)) {
+                return false;
+            }
+        }
+
+        if let Some(attribute) = self.attribute {
+            if !card.attribute.contains(&attribute) {
+                return false;
+            }
+        }
+
+        if let Some(keyword) = self.keyword {
+            if !card.effects.iter().any(|e| effect_has_keyword(e, keyword)) {
+                return false;
+            }
+        }
+
+        if let Some(set) = &self.set {
+            if !card.set.eq_ignore_ascii_case(set) {
+                return false;
+            }
+        }
+
+        if self.rarity.is_some() && card.rarity != self.rarity {
+            return false;
+        }
+
+        if self.min_block.is_some_and(|n| card.block < n)
+            || self.max_block.is_some_and(|n| card.block > n)
+        {
+            return false;
+        }
+
+        // ISO dates compare correctly as strings.
+        if let Some(date) = &self.released_by {
+            if card.release_date.is_empty() || card.release_date.as_str() > date.as_str() {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl CardQuery {
+    /// Sets the filter for a command line option like `--cost 2..4`. Returns `false` for options
+    /// that aren't filters, so tools can handle their own.
+    pub fn set_option(&mut self, option: &str, value: &str) -> Result<bool, String> {
+        match option {
+            "--color" => self.color = Some(value.parse()?),
+            "--category" => self.category = Some(value.parse()?),
+            "--cost" => (self.min_cost, self.max_cost) = parse_range(value)?,
+            "--power" => (self.min_power, self.max_power) = parse_range(value)?,
+            "--type" => self.types = Some(value.to_string()),
+            "--attribute" => self.attribute = Some(value.parse()?),
+            "--keyword" => self.keyword = Some(value.parse()?),
+            "--set" => self.set = Some(value.to_string()),
+            "--rarity" => self.rarity = Some(value.parse()?),
+            "--block" => (self.min_block, self.max_block) = parse_range(value)?,
+            "--released-by" => self.released_by = Some(value.to_string()),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+/// The filters `CardQuery::set_option` understands, for usage messages.
+pub const CARD_QUERY_USAGE: &str = "  --color <color>          e.g. red, or R
+  --category <category>    leader, character, event, stage or don
+  --cost <n|min..max>      e.g. 2, 3.., ..4 or 2..4
+  --power <n|min..max>     e.g. 5000 or 4000..6000
+  --type <text>            part of a type, e.g. straw hat
+  --attribute <attribute>  e.g. slash, or SL
+  --keyword <keyword>      blocker, rush, double_attack or banish
+  --set <code>             e.g. ST01
+  --rarity <rarity>        e.g. super_rare, or SR
+  --block <n|min..max>     e.g. 1 or 2..
+  --released-by <date>     released on or before YYYY-MM-DD";
+
+// `n` is exactly `n`, while `min..max` can leave out either end.
+fn parse_range(range: &str) -> Result<(Option<i32>, Option<i32>), String> {
+    let parse = |s: &str| -> Result<Option<i32>, String> {
+        if s.is_empty() {
+            return Ok(None);
+        }
+        s.parse::<i32>()
+            .map(Some)
+            .map_err(|_| format!("`{s}` is not a number"))
+    };
+
+    match range.split_once("..") {
+        Some((min, max)) => Ok((parse(min)?, parse(max)?)),
+        None => {
+            let n = parse(range)?;
+            Ok((n, n))
+        }
+    }
+}
+
+fn effect_has_keyword(effect: &Effect, keyword: Keyword) -> bool {
+    match effect {
+        Effect::TimedEffect(_, _, effects) | Effect::Replacement(_, _, effects) => {
+            effects.iter().any(|e| effect_has_keyword(e, keyword))
+        }
+        effect => effect.keyword() == Some(keyword),
+    }
+}
+
+impl CardDatabase {
+    pub fn query(&self, query: &CardQuery) -> Vec<&Card> {
+        self.cards().filter(|card| query.matches(card)).collect()
+    }
+}
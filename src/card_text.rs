@@ -0,0 +1,197 @@
+use super::{card::*, game::*};
+
+// Generates rulings-style English card text from `Effect` trees, e.g.
+// `[DON!! x1] [When Attacking] Your opponent cannot activate [Blocker] during this battle.`
+// Reminder text (the parenthesised explanations printed after keywords) is never generated.
+
+/// The full text of a card, one line per top-level effect.
+pub fn card_text(card: &Card) -> String {
+    let subject = subject_for(card.category);
+    card.effects
+        .iter()
+        .map(|effect| top_level_text(effect, subject))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// The text of a single effect as it would appear on a Character.
+pub fn effect_text(effect: &Effect) -> String {
+    top_level_text(effect, "Character")
+}
+
+fn subject_for(category: CardCategory) -> &'static str {
+    match category {
+        CardCategory::Leader(_) => "Leader",
+        CardCategory::Character => "Character",
+        CardCategory::Event => "Event",
+        CardCategory::Stage => "Stage",
+        CardCategory::Don => "DON!! card",
+    }
+}
+
+fn top_level_text(effect: &Effect, subject: &str) -> String {
+    match effect {
+        Effect::TimedEffect(timing, cost, effects) => timed_text(*timing, *cost, effects, subject),
+        Effect::Replacement(event, cost, effects) => replacement_text(*event, *cost, effects, subject),
+        // a bare power bonus is how DON!! cards describe what they give.
+        Effect::PlusPower(n) if subject == "DON!! card" => {
+            format!("[Your Turn] The Leader or Character this card is given to gains +{n} power.")
+        }
+        Effect::PlusPower(_) | Effect::MinusCost(_) => sentence(effect, true, subject),
+        effect => match effect.keyword() {
+            Some(_) => keyword_text(effect),
+            None => sentence(effect, false, subject),
+        },
+    }
+}
+
+fn timed_text(timing: Timing, cost: EffectCost, effects: &[Effect], subject: &str) -> String {
+    let mut labels = vec![];
+
+    if let EffectCost::DonAttached(n) = cost {
+        labels.push(format!("[DON!! x{n}]"));
+    }
+    let timing_label = timing.to_string();
+    if !timing_label.is_empty() {
+        labels.push(timing_label);
+    }
+    if effects.iter().any(|e| matches!(e, Effect::OncePerTurn)) {
+        labels.push("[Once Per Turn]".to_string());
+    }
+
+    let continuous = matches!(
+        timing,
+        Timing::Always | Timing::DuringTurn | Timing::DuringOpponentsTurn
+    );
+    let body = effects
+        .iter()
+        .filter(|e| !matches!(e, Effect::OncePerTurn))
+        .map(|e| sentence(e, continuous, subject))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    let body = match cost {
+        EffectCost::MinusDon(n) => format!("DON!! -{n}: {body}"),
+        EffectCost::RestDon(n) => format!("Rest {}: {body}", plural(n, "DON!! card", "DON!! cards")),
+        EffectCost::DonAttached(_) | EffectCost::Zero => body,
+    };
+
+    labels.push(body);
+    labels.join(" ")
+}
+
+fn replacement_text(event: Replaceable, cost: EffectCost, effects: &[Effect], subject: &str) -> String {
+    let condition = match event {
+        Replaceable::KnockOut => format!("If this {subject} would be K.O.'d"),
+        Replaceable::LeaveField => format!("If this {subject} would be removed from the field"),
+        Replaceable::Damage => "If your Leader would take damage".to_string(),
+    };
+
+    let mut actions = vec![];
+    match cost {
+        EffectCost::MinusDon(n) => actions.push(format!(
+            "return {} from your field to your DON!! deck",
+            plural(n, "DON!! card", "DON!! cards")
+        )),
+        EffectCost::RestDon(n) => actions.push(format!("rest {n} of your DON!! cards")),
+        EffectCost::DonAttached(_) | EffectCost::Zero => {}
+    }
+    for effect in effects.iter() {
+        actions.push(clause(&sentence(effect, false, subject)));
+    }
+
+    let prefix = match cost {
+        EffectCost::DonAttached(n) => format!("[DON!! x{n}] "),
+        _ => String::new(),
+    };
+
+    format!("{prefix}{condition}, you may {} instead.", actions.join(" and "))
+}
+
+fn keyword_text(effect: &Effect) -> String {
+    match effect.keyword() {
+        Some(Keyword::Blocker) => "[Blocker]".to_string(),
+        Some(Keyword::Rush) => "[Rush]".to_string(),
+        Some(Keyword::DoubleAttack) => "[Double Attack]".to_string(),
+        Some(Keyword::Banish) => "[Banish]".to_string(),
+        None => String::new(),
+    }
+}
+
+// A single effect as a full sentence. `continuous` effects describe what the card itself has,
+// while the others are one-off effects that pick a target.
+fn sentence(effect: &Effect, continuous: bool, subject: &str) -> String {
+    match effect {
+        Effect::Blocker | Effect::Rush | Effect::DoubleAttack | Effect::Banish => {
+            format!("This {subject} gains {}.", keyword_text(effect))
+        }
+        Effect::Draw(n) => format!("Draw {}.", plural(*n, "card", "cards")),
+        Effect::GiveOtherCardPower(n) => format!(
+            "Up to 1 of your Leader or Character cards other than this card gains +{n} power during this turn."
+        ),
+        Effect::GiveRestedDon(n) => format!(
+            "Give up to {} to your Leader or 1 of your Characters.",
+            plural(*n, "rested DON!! card", "rested DON!! cards")
+        ),
+        Effect::KnockOutWithPowerEqualOrLessThan(n) => format!(
+            "K.O. up to 1 of your opponent's Characters with {n} power or less."
+        ),
+        Effect::OncePerTurn => "[Once Per Turn]".to_string(),
+        Effect::OpponentNoBlocker(Condition::None) => {
+            "Your opponent cannot activate [Blocker] during this battle.".to_string()
+        }
+        Effect::OpponentNoBlocker(Condition::PowerAndAbove(n)) => format!(
+            "Your opponent cannot activate a [Blocker] Character that has {n} or more power during this battle."
+        ),
+        Effect::PlayCard => "Play this card.".to_string(),
+        Effect::PlusPower(n) => {
+            if continuous {
+                format!("This {subject} gains +{n} power.")
+            } else {
+                format!("Up to 1 of your Leader or Character cards gains +{n} power during this turn.")
+            }
+        }
+        Effect::PlusPowerForBattle(n) => format!(
+            "Up to 1 of your Leader or Character cards gains +{n} power during this battle."
+        ),
+        Effect::MinusCost(n) => {
+            if continuous {
+                format!("This {subject} has -{n} cost.")
+            } else {
+                format!("Give this {subject} -{n} cost during this turn.")
+            }
+        }
+        Effect::YourCharacters(effects) => {
+            let changes: Vec<String> = effects
+                .iter()
+                .map(|effect| match effect {
+                    Effect::PlusPower(n) => format!("gain +{n} power"),
+                    Effect::MinusCost(n) => format!("have -{n} cost"),
+                    effect if effect.keyword().is_some() => format!("gain {}", keyword_text(effect)),
+                    effect => clause(&sentence(effect, continuous, "Character")),
+                })
+                .collect();
+            format!("All of your Characters {}.", changes.join(" and "))
+        }
+        Effect::TimedEffect(timing, cost, effects) => timed_text(*timing, *cost, effects, subject),
+        Effect::Replacement(event, cost, effects) => replacement_text(*event, *cost, effects, subject),
+    }
+}
+
+// Turns a sentence into a clause that can follow "you may", e.g. "Draw 1 card." -> "draw 1 card".
+fn clause(sentence: &str) -> String {
+    let sentence = sentence.trim_end_matches('.');
+    let mut chars = sentence.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn plural(n: i32, singular: &str, plural: &str) -> String {
+    if n == 1 {
+        format!("{n} {singular}")
+    } else {
+        format!("{n} {plural}")
+    }
+}
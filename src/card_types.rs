@@ -0,0 +1,78 @@
+// The vocabulary of card types, spelled as they are printed on English cards. Card data must
+// only use these, so that type searches and "[Straw Hat Crew] type" effects match every card.
+
+pub const CARD_TYPES: &[&str] = &[
+    "Alabasta",
+    "Animal",
+    "Animal Kingdom Pirates",
+    "Baroque Works",
+    "Big Mom Pirates",
+    "Biological Weapon",
+    "Blackbeard Pirates",
+    "CP0",
+    "CP9",
+    "Celestial Dragons",
+    "Donquixote Pirates",
+    "Dressrosa",
+    "East Blue",
+    "Egghead",
+    "FILM",
+    "Fish-Man",
+    "Fish-Man Island",
+    "Former Navy",
+    "Heart Pirates",
+    "Impel Down",
+    "Kid Pirates",
+    "Land of Wano",
+    "Merfolk",
+    "Minks",
+    "Navy",
+    "Red-Haired Pirates",
+    "Revolutionary Army",
+    "Sky Island",
+    "Straw Hat Crew",
+    "Supernovas",
+    "The Four Emperors",
+    "The Seven Warlords of the Sea",
+    "Thriller Bark Pirates",
+    "Water Seven",
+    "Whitebeard Pirates",
+    "World Government",
+];
+
+// Common misspellings and older translations, and the type they mean.
+const CARD_TYPE_ALIASES: &[(&str, &str)] = &[
+    ("Fishman", "Fish-Man"),
+    ("Fishman Island", "Fish-Man Island"),
+    ("Straw Hat Pirates", "Straw Hat Crew"),
+    ("Supernova", "Supernovas"),
+    ("Four Emperors", "The Four Emperors"),
+    ("Seven Warlords of the Sea", "The Seven Warlords of the Sea"),
+    ("Wano Country", "Land of Wano"),
+    ("Red Hair Pirates", "Red-Haired Pirates"),
+];
+
+/// The canonical spelling of a card type, ignoring case, spaces and punctuation,
+/// or `None` if it isn't part of the vocabulary.
+pub fn normalise_type(card_type: &str) -> Option<&'static str> {
+    let key = type_key(card_type);
+
+    CARD_TYPES
+        .iter()
+        .copied()
+        .find(|t| type_key(t) == key)
+        .or_else(|| {
+            CARD_TYPE_ALIASES
+                .iter()
+                .find(|(alias, _)| type_key(alias) == key)
+                .map(|(_, t)| *t)
+        })
+}
+
+fn type_key(card_type: &str) -> String {
+    card_type
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
@@ -0,0 +1,208 @@
+use serde_json::Value;
+
+use super::{card::*, card_definition::*, card_types::*, game::*, utils::*};
+
+// Semantic checks on card data, beyond what deserialising it already enforces. Every problem in
+// every file is collected so the whole card data directory can be fixed in one pass.
+
+#[derive(Debug, Clone)]
+pub struct CardProblem {
+    pub file: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for CardProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.file, self.message)
+    }
+}
+
+/// Every problem with every card file in `dir`. An empty list means the card data is valid.
+pub fn validate_card_data(dir: &str) -> Vec<CardProblem> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            return vec![CardProblem {
+                file: dir.to_string(),
+                message: e.to_string(),
+            }]
+        }
+    };
+
+    let mut files: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .map(|path| path.display().to_string())
+        .collect();
+    files.sort();
+
+    let mut problems = vec![];
+    for file in files.iter() {
+        let file_name = std::path::Path::new(file)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+
+        let messages = match std::fs::read_to_string(file) {
+            Ok(card_data) => validate_card_file(file_name, &card_data),
+            Err(e) => vec![e.to_string()],
+        };
+
+        problems.extend(messages.into_iter().map(|message| CardProblem {
+            file: file.clone(),
+            message,
+        }));
+    }
+
+    problems
+}
+
+/// Every problem with the card data stored in a file called `file_name`.
+pub fn validate_card_file(file_name: &str, card_data: &str) -> Vec<String> {
+    let raw: Value = match serde_json::from_str(card_data) {
+        Ok(raw) => raw,
+        Err(e) => return vec![e.to_string()],
+    };
+
+    let mut problems = runtime_field_problems(&raw);
+
+    let definition = match load_card_definition(card_data) {
+        Ok(definition) => definition,
+        Err(e) => {
+            problems.push(e.to_string());
+            return problems;
+        }
+    };
+
+    let expected = card_file_name(&definition);
+    if file_name != expected {
+        problems.push(format!("file name should be {expected}"));
+    }
+
+    problems.extend(validate_card_definition(&definition));
+    problems
+}
+
+/// Every problem with a single card definition, independent of where it is stored.
+pub fn validate_card_definition(definition: &CardDefinition) -> Vec<String> {
+    let mut problems = vec![];
+
+    let effects = match definition.to_card() {
+        Ok(card) => card.effects,
+        Err(e) => {
+            problems.push(CardDataError::Script(e).to_string());
+            vec![]
+        }
+    };
+
+    match definition.category {
+        CardCategory::Leader(life) => {
+            if life <= 0 {
+                problems.push(format!("Leader must have a positive life total, not {life}"));
+            }
+            if definition.cost.0 != 0 {
+                problems.push(format!("Leader must have no cost, not {}", definition.cost.0));
+            }
+            if definition.power.is_none() {
+                problems.push("Leader must have power".to_string());
+            }
+            if definition.counter_power.is_some() {
+                problems.push("Leader must not have a counter".to_string());
+            }
+        }
+        CardCategory::Character => {
+            if definition.power.is_none() {
+                problems.push("Character must have power".to_string());
+            }
+        }
+        CardCategory::Event => {
+            // `main_step` only knows how to play Events whose first effect is a Main or Counter timing.
+            match effects.first() {
+                Some(Effect::TimedEffect(Timing::Main | Timing::Counter, _, _)) => {}
+                _ => problems.push("Event's first effect must have a Main or Counter timing".to_string()),
+            }
+            if definition.power.is_some() || definition.counter_power.is_some() {
+                problems.push("Event must not have power or a counter".to_string());
+            }
+        }
+        CardCategory::Stage => {
+            if definition.power.is_some() || definition.counter_power.is_some() {
+                problems.push("Stage must not have power or a counter".to_string());
+            }
+        }
+        CardCategory::Don => {}
+    }
+
+    if definition.identifier.is_empty() {
+        problems.push("identifier must not be empty".to_string());
+    }
+    if definition.arts.is_empty() {
+        problems.push("card must have at least one art".to_string());
+    }
+    for (i, art) in definition.arts.iter().enumerate() {
+        if art.is_empty() {
+            problems.push("art must not be empty".to_string());
+        } else if definition.arts[..i].contains(art) {
+            problems.push(format!("art {art} is listed more than once"));
+        }
+    }
+    for card_type in definition.types.iter() {
+        match normalise_type(card_type) {
+            Some(t) if t == card_type => {}
+            Some(t) => problems.push(format!("type `{card_type}` should be written `{t}`")),
+            None => problems.push(format!("unknown type `{card_type}`")),
+        }
+    }
+    if definition.text.is_empty() && (!effects.is_empty() || definition.script.is_some()) {
+        problems.push("card with effects must have its printed text".to_string());
+    }
+
+    if !definition.set.is_empty() && !definition.identifier.starts_with(&format!("{}-", definition.set)) {
+        problems.push(format!("set {} does not match identifier {}", definition.set, definition.identifier));
+    }
+    if !definition.set.is_empty() && definition.rarity.is_none() {
+        problems.push(format!("card from set {} must have a rarity", definition.set));
+    }
+    if !definition.release_date.is_empty() && !is_iso_date(&definition.release_date) {
+        problems.push(format!("release date {} must be written as YYYY-MM-DD", definition.release_date));
+    }
+    if definition.block < 0 {
+        problems.push(format!("block must not be negative, not {}", definition.block));
+    }
+    if definition.cost.0 < 0 {
+        problems.push(format!("cost must not be negative, not {}", definition.cost.0));
+    }
+
+    problems
+}
+
+fn is_iso_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    parts.len() == 3
+        && [4, 2, 2].iter().zip(parts.iter()).all(|(len, part)| {
+            part.len() == *len && part.chars().all(|c| c.is_ascii_digit())
+        })
+}
+
+// Game state fields that only belong on a `Card` in play. Migration drops these from older
+// files, so any that are left over in a current file would be silently ignored.
+fn runtime_field_problems(raw: &Value) -> Vec<String> {
+    let mut problems = vec![];
+
+    if schema_version(raw) == 0 {
+        for field in ["attached_don", "status"] {
+            if raw.get(field).and_then(|v| v.as_array()).is_some_and(|v| !v.is_empty()) {
+                problems.push(format!("runtime field `{field}` must be empty"));
+            }
+        }
+    } else {
+        for field in ["attached_don", "status", "facing"] {
+            if raw.get(field).is_some() {
+                problems.push(format!("runtime field `{field}` is not part of the card schema"));
+            }
+        }
+    }
+
+    problems
+}
@@ -0,0 +1,169 @@
+use super::{card::*, card_database::*, deck_formats::*, format::*, utils::*};
+
+pub const MAIN_DECK_SIZE: usize = 50;
+pub const DON_DECK_SIZE: usize = 10;
+
+/// A deck put together one card at a time, e.g. by a deck editor. Any change is allowed, even
+/// one that makes the deck illegal, and `validate` says what is wrong with it using the same
+/// rules as `validate_deck`. Cards are kept with the format's errata applied.
+#[derive(Debug, Clone)]
+pub struct DeckBuilder<'a> {
+    card_database: &'a CardDatabase,
+    format: &'a Format,
+    leader: Option<Card>,
+    main_deck: Deck,
+    don_deck: Deck,
+}
+
+impl<'a> DeckBuilder<'a> {
+    /// An empty deck with the usual 10 DON!! cards, when the card database has them.
+    pub fn new(card_database: &'a CardDatabase, format: &'a Format) -> DeckBuilder<'a> {
+        let don_deck = match card_database.get(DEFAULT_DON, "") {
+            Ok(don) => vec![format.apply_errata(don); DON_DECK_SIZE],
+            Err(_) => vec![],
+        };
+
+        DeckBuilder {
+            card_database,
+            format,
+            leader: None,
+            main_deck: vec![],
+            don_deck,
+        }
+    }
+
+    /// Starts from an existing deck, e.g. one read with `parse_deck_list`.
+    pub fn from_deck(
+        card_database: &'a CardDatabase,
+        format: &'a Format,
+        leader: &Card,
+        main_deck: &Deck,
+        don_deck: &Deck,
+    ) -> DeckBuilder<'a> {
+        DeckBuilder {
+            card_database,
+            format,
+            leader: Some(format.apply_errata(leader)),
+            main_deck: main_deck.iter().map(|card| format.apply_errata(card)).collect(),
+            don_deck: don_deck.iter().map(|card| format.apply_errata(card)).collect(),
+        }
+    }
+
+    pub fn leader(&self) -> Option<&Card> {
+        self.leader.as_ref()
+    }
+
+    pub fn main_deck(&self) -> &Deck {
+        &self.main_deck
+    }
+
+    pub fn don_deck(&self) -> &Deck {
+        &self.don_deck
+    }
+
+    /// Replaces the Leader. An empty `art` picks the card's base art, as in deck lists.
+    pub fn set_leader(&mut self, identifier: &str, art: &str) -> Result<(), DeckError> {
+        let card = self.card_database.get(identifier, art)?;
+        if !card.is_leader() {
+            return Err(DeckError::NotALeader(identifier.to_string()));
+        }
+
+        self.leader = Some(self.format.apply_errata(card));
+        Ok(())
+    }
+
+    /// Adds `quantity` copies of a main deck or DON!! card. Leaders go in with `set_leader`.
+    pub fn add(&mut self, identifier: &str, art: &str, quantity: i32) -> Result<(), DeckError> {
+        if quantity < 0 {
+            return Err(DeckError::InvalidQuantity(quantity.to_string()));
+        }
+        let card = self.card_database.get(identifier, art)?;
+        if card.is_leader() {
+            return Err(DeckError::LeaderInMainDeck(identifier.to_string()));
+        }
+
+        let card = self.format.apply_errata(card);
+        let deck = if card.is_don() { &mut self.don_deck } else { &mut self.main_deck };
+        for _ in 0..quantity {
+            deck.push(card.clone());
+        }
+        Ok(())
+    }
+
+    /// Removes up to `quantity` copies of a card and returns how many were removed. An empty
+    /// `art` removes copies of any art.
+    pub fn remove(&mut self, identifier: &str, art: &str, quantity: i32) -> i32 {
+        let mut removed = 0;
+        for deck in [&mut self.main_deck, &mut self.don_deck] {
+            deck.retain(|card| {
+                let matches = card.identifier == identifier && (art.is_empty() || card.art == art);
+                if matches && removed < quantity {
+                    removed += 1;
+                    return false;
+                }
+                true
+            });
+        }
+        removed
+    }
+
+    /// Copies of `identifier` in the main deck, counting every art.
+    pub fn copies(&self, identifier: &str) -> i32 {
+        self.main_deck.iter().filter(|card| card.identifier == identifier).count() as i32
+    }
+
+    /// Main deck slots left before the deck has 50 cards.
+    pub fn remaining_slots(&self) -> usize {
+        MAIN_DECK_SIZE.saturating_sub(self.main_deck.len())
+    }
+
+    /// How many more copies of `identifier` can be added without making the deck illegal. Cards
+    /// that could never be legal in this deck, like ones outside the Leader's colors, have none.
+    pub fn remaining_copies(&self, identifier: &str) -> i32 {
+        let Ok(card) = self.card_database.get(identifier, "") else {
+            return 0;
+        };
+
+        if card.is_leader() {
+            return 0;
+        }
+        if card.is_don() {
+            return DON_DECK_SIZE.saturating_sub(self.don_deck.len()) as i32;
+        }
+        if self.format.is_rotated_out(card) {
+            return 0;
+        }
+        if let Some(leader) = &self.leader {
+            if !card.color.iter().any(|color| leader.color.contains(color)) {
+                return 0;
+            }
+        }
+
+        let copies_left = (self.format.copy_limit(identifier) - self.copies(identifier)).max(0);
+        copies_left.min(self.remaining_slots() as i32)
+    }
+
+    /// Every card in the deck, Leader first, as `validate_deck` takes them.
+    pub fn cards(&self) -> Vec<Card> {
+        self.leader
+            .iter()
+            .chain(self.main_deck.iter())
+            .chain(self.don_deck.iter())
+            .cloned()
+            .collect()
+    }
+
+    pub fn validate(&self) -> Result<(), Vec<DeckError>> {
+        validate_deck(&self.cards(), self.format)
+    }
+
+    pub fn is_legal(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// The finished deck, in the same shape as `parse_deck_list` gives it.
+    pub fn build(&self) -> Result<(Card, Deck, Deck), Vec<DeckError>> {
+        self.validate()?;
+        Ok((self.leader.clone().unwrap(), self.main_deck.clone(), self.don_deck.clone()))
+    }
+}
@@ -0,0 +1,298 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::{card::*, card_database::*, deck_builder::DON_DECK_SIZE, format::*, utils::*};
+
+// Deck lists in the formats players share them in. Every format is read by turning it into our
+// own `sample_deck.txt` style, so `parse_deck_list` does all of the checking, and
+// `ConvertedDeckList::locate` points its diagnostics back at the list as it was given.
+//
+//   Optcg      `4 ST01-006 Tony Tony Chopper (P0)`, our own.
+//   Simulator  `4xST01-006`, as exported by online simulators. DON!! isn't listed.
+//   Json       `["ST01-001", "ST01-006", "ST01-006_p1", ...]`, one id per copy with the Leader
+//              first and alternate art as a suffix. DON!! isn't listed.
+
+/// The DON!! card added to decks from formats that don't list DON!!.
+pub const DEFAULT_DON: &str = "DON-don";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeckListFormat {
+    Optcg,
+    Simulator,
+    Json,
+}
+
+impl FromStr for DeckListFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "optcg" | "txt" => Ok(DeckListFormat::Optcg),
+            "simulator" | "sim" => Ok(DeckListFormat::Simulator),
+            "json" => Ok(DeckListFormat::Json),
+            _ => Err(format!("unknown deck list format `{s}`")),
+        }
+    }
+}
+
+impl fmt::Display for DeckListFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeckListFormat::Optcg => write!(f, "optcg"),
+            DeckListFormat::Simulator => write!(f, "simulator"),
+            DeckListFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+pub fn detect_deck_list_format(deck_list: &str) -> DeckListFormat {
+    if deck_list.trim_start().starts_with('[') {
+        return DeckListFormat::Json;
+    }
+
+    let first_line = deck_list
+        .lines()
+        .map(|line| line.trim())
+        .find(|line| !line.is_empty() && !line.starts_with("//"));
+    match first_line.and_then(|line| line.split_once('x')) {
+        Some((quantity, id))
+            if !quantity.is_empty()
+                && quantity.chars().all(|c| c.is_ascii_digit())
+                && !id.is_empty()
+                && !id.contains(char::is_whitespace) =>
+        {
+            DeckListFormat::Simulator
+        }
+        _ => DeckListFormat::Optcg,
+    }
+}
+
+/// Reads a deck list in any supported format, detecting which one it is. Diagnostics point at
+/// the deck list as it was given, not at the converted one.
+pub fn import_deck_list(
+    deck_list: &str,
+    card_database: &CardDatabase,
+    format: &Format,
+) -> Result<(Card, Deck, Deck), Vec<DeckDiagnostic>> {
+    let converted = convert_deck_list(deck_list, detect_deck_list_format(deck_list))?;
+    parse_deck_list(&converted.deck_list, card_database, format).map_err(|diagnostics| converted.locate(diagnostics))
+}
+
+/// Rewrites a deck list in `from` as our own format, without checking the cards.
+pub fn to_optcg_deck_list(deck_list: &str, from: DeckListFormat) -> Result<String, Vec<DeckDiagnostic>> {
+    convert_deck_list(deck_list, from).map(|converted| converted.deck_list)
+}
+
+/// A deck list rewritten in our own format, which remembers where each of its lines came from.
+#[derive(Debug, Clone)]
+pub struct ConvertedDeckList {
+    pub deck_list: String,
+    sources: Vec<LineSource>, // One per line of `deck_list`.
+}
+
+#[derive(Debug, Clone)]
+enum LineSource {
+    Same,                      // The same line of the original.
+    Added,                     // Not in the original at all, like the default DON!!.
+    At(usize, usize, String), // The line, column and text of the original it was made from.
+}
+
+impl ConvertedDeckList {
+    /// Moves diagnostics about the converted deck list onto the original one. Diagnostics about
+    /// lines the original doesn't have become diagnostics about the deck as a whole.
+    pub fn locate(&self, diagnostics: Vec<DeckDiagnostic>) -> Vec<DeckDiagnostic> {
+        diagnostics
+            .into_iter()
+            .map(|mut diagnostic| {
+                if diagnostic.line == 0 {
+                    return diagnostic;
+                }
+                match self.sources.get(diagnostic.line - 1) {
+                    Some(LineSource::Same) | None => {}
+                    Some(LineSource::Added) => {
+                        diagnostic.line = 0;
+                        diagnostic.column = 0;
+                        diagnostic.text = String::new();
+                    }
+                    Some(LineSource::At(line, column, text)) => {
+                        diagnostic.line = *line;
+                        diagnostic.column = *column;
+                        diagnostic.text = text.clone();
+                    }
+                }
+                diagnostic
+            })
+            .collect()
+    }
+}
+
+/// `to_optcg_deck_list`, keeping track of where each line came from.
+pub fn convert_deck_list(deck_list: &str, from: DeckListFormat) -> Result<ConvertedDeckList, Vec<DeckDiagnostic>> {
+    match from {
+        DeckListFormat::Optcg => Ok(ConvertedDeckList {
+            deck_list: deck_list.to_string(),
+            sources: vec![],
+        }),
+        DeckListFormat::Simulator => {
+            // `4xST01-006` becomes `4 ST01-006`, which keeps the card id in the same column.
+            let mut lines: Vec<String> = deck_list
+                .lines()
+                .map(|line| match line.trim().split_once('x') {
+                    Some((quantity, card)) if !line.trim().starts_with("//") => {
+                        let (id, art) = split_art_suffix(card);
+                        deck_list_line(quantity, &id, &art)
+                    }
+                    // anything else is left for `parse_deck_list` to report on the same line.
+                    _ => line.to_string(),
+                })
+                .collect();
+            let mut sources = vec![LineSource::Same; lines.len()];
+            if !deck_list.contains(DEFAULT_DON) {
+                lines.push(deck_list_line(DON_DECK_SIZE, DEFAULT_DON, ""));
+                sources.push(LineSource::Added);
+            }
+            Ok(ConvertedDeckList {
+                deck_list: lines.join("\n"),
+                sources,
+            })
+        }
+        DeckListFormat::Json => {
+            let ids: Vec<String> = serde_json::from_str(deck_list).map_err(|e| {
+                vec![DeckDiagnostic {
+                    line: e.line(),
+                    column: e.column(),
+                    text: String::new(),
+                    error: DeckError::UnreadableDeckList(e.to_string()),
+                }]
+            })?;
+            let positions = json_string_positions(deck_list);
+
+            // each entry points at the first copy of its card in the array.
+            let mut entries: Vec<DeckListEntry> = vec![];
+            let mut sources = vec![];
+            for (i, card) in ids.iter().enumerate() {
+                let (id, art) = split_art_suffix(card);
+                match entries.iter_mut().find(|e| e.id == id && e.art == art) {
+                    Some(entry) => entry.quantity += 1,
+                    None => {
+                        entries.push(DeckListEntry { quantity: 1, id, art });
+                        let (line, column) = positions.get(i).copied().unwrap_or_default();
+                        sources.push(LineSource::At(line, column, card.clone()));
+                    }
+                }
+            }
+
+            let mut lines: Vec<String> = entries
+                .iter()
+                .map(|e| deck_list_line(e.quantity, &e.id, &e.art))
+                .collect();
+            if !entries.iter().any(|e| e.id == DEFAULT_DON) {
+                lines.push(deck_list_line(DON_DECK_SIZE, DEFAULT_DON, ""));
+                sources.push(LineSource::Added);
+            }
+            Ok(ConvertedDeckList {
+                deck_list: lines.join("\n"),
+                sources,
+            })
+        }
+    }
+}
+
+// The line and column of the first character inside each string of a JSON array of strings.
+fn json_string_positions(json: &str) -> Vec<(usize, usize)> {
+    let mut positions = vec![];
+    let (mut line, mut column) = (1, 0);
+    let (mut in_string, mut escaped) = (false, false);
+
+    for c in json.chars() {
+        column += 1;
+        if c == '\n' {
+            line += 1;
+            column = 0;
+        } else if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+        } else if c == '"' {
+            in_string = true;
+            positions.push((line, column + 1));
+        }
+    }
+
+    positions
+}
+
+// `ST01-006_p1` is art P1 of ST01-006, and a plain id is the base art.
+fn split_art_suffix(card: &str) -> (String, String) {
+    match card.trim().split_once('_') {
+        Some((id, art)) => (id.to_string(), art.to_uppercase()),
+        None => (card.trim().to_string(), String::new()),
+    }
+}
+
+// a line without an art is read as the card's base art.
+fn deck_list_line(quantity: impl fmt::Display, id: &str, art: &str) -> String {
+    match art {
+        "" => format!("{quantity} {id}"),
+        art => format!("{quantity} {id} ({art})"),
+    }
+}
+
+/// The cards of a deck grouped into deck list entries, Leader first, then the main deck in the
+/// order each card first appears, then DON!!.
+pub fn deck_list_entries(leader: &Card, main_deck: &Deck, don_deck: &Deck) -> Vec<DeckListEntry> {
+    let mut entries: Vec<DeckListEntry> = vec![];
+
+    for card in std::iter::once(leader).chain(main_deck.iter()).chain(don_deck.iter()) {
+        match entries.iter_mut().find(|e| e.id == card.identifier && e.art == card.art) {
+            Some(entry) => entry.quantity += 1,
+            None => entries.push(DeckListEntry {
+                quantity: 1,
+                id: card.identifier.clone(),
+                art: card.art.clone(),
+            }),
+        }
+    }
+
+    entries
+}
+
+pub fn export_deck_list(leader: &Card, main_deck: &Deck, don_deck: &Deck, to: DeckListFormat) -> String {
+    match to {
+        DeckListFormat::Optcg => {
+            let mut lines = vec![format!("// {}", leader.name)];
+            let cards = std::iter::once(leader).chain(main_deck.iter()).chain(don_deck.iter());
+            for entry in deck_list_entries(leader, main_deck, don_deck) {
+                let name = cards
+                    .clone()
+                    .find(|card| card.identifier == entry.id)
+                    .map(|card| card.name.clone())
+                    .unwrap_or_default();
+                lines.push(format!("{} {} {} ({})", entry.quantity, entry.id, name, entry.art));
+            }
+            lines.join("\n")
+        }
+        DeckListFormat::Simulator => deck_list_entries(leader, main_deck, &vec![])
+            .iter()
+            .map(|e| format!("{}x{}", e.quantity, with_art_suffix(&e.id, &e.art)))
+            .collect::<Vec<String>>()
+            .join("\n"),
+        DeckListFormat::Json => {
+            let ids: Vec<String> = std::iter::once(leader)
+                .chain(main_deck.iter())
+                .map(|card| with_art_suffix(&card.identifier, &card.art))
+                .collect();
+            serde_json::to_string(&ids).unwrap()
+        }
+    }
+}
+
+fn with_art_suffix(id: &str, art: &str) -> String {
+    match art {
+        "P0" | "" => id.to_string(),
+        art => format!("{id}_{}", art.to_lowercase()),
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::{card::*, card_database::*, deck_formats::*, utils::*};
+
+// Comparing deck lists works on the lines of the lists alone, so a deck that has stopped being
+// legal, or uses cards this card database doesn't have, can still be compared and kept. The card
+// database is only asked which art is the base art of each card.
+
+/// How the copies of one card changed between two deck lists. Arts count as the same card, and
+/// `arts` lists the copies of each art that changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CardDiff {
+    pub id: String,
+    pub before: i32,
+    pub after: i32,
+    pub arts: Vec<ArtDiff>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ArtDiff {
+    pub art: String,
+    pub base: bool, // Whether `art` is the card's base art, which a line without an art stands for.
+    pub before: i32,
+    pub after: i32,
+}
+
+impl CardDiff {
+    pub fn change(&self) -> i32 {
+        self.after - self.before
+    }
+}
+
+// e.g. `+1 ST01-007  4 -> 5  (P0 -1, P1 +2)`.
+impl fmt::Display for CardDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:+} {}  {} -> {}", self.change(), self.id, self.before, self.after)?;
+        // only a change to the base art goes without saying.
+        if self.arts.iter().any(|a| !a.base) {
+            let arts: Vec<String> = self
+                .arts
+                .iter()
+                .map(|a| format!("{} {:+}", a.art, a.after - a.before))
+                .collect();
+            write!(f, "  ({})", arts.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The lines of a deck list in any supported format, without looking the cards up.
+pub fn read_deck_list_entries(deck_list: &str) -> Result<Vec<DeckListEntry>, Vec<DeckDiagnostic>> {
+    let converted = convert_deck_list(deck_list, detect_deck_list_format(deck_list))?;
+    let (entries, diagnostics) = parse_deck_list_entries(&converted.deck_list);
+    if !diagnostics.is_empty() {
+        return Err(converted.locate(diagnostics));
+    }
+    Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+}
+
+/// Every card whose copies changed from `before` to `after`, ordered by identifier.
+pub fn diff_deck_list_entries(
+    before: &[DeckListEntry],
+    after: &[DeckListEntry],
+    card_database: &CardDatabase,
+) -> Vec<CardDiff> {
+    // identifier -> art -> (before, after). A line without an art is the base art.
+    let mut counts: BTreeMap<&str, BTreeMap<&str, (i32, i32)>> = BTreeMap::new();
+    for entry in before.iter() {
+        let art = art(entry, card_database);
+        counts.entry(&entry.id).or_default().entry(art).or_default().0 += entry.quantity;
+    }
+    for entry in after.iter() {
+        let art = art(entry, card_database);
+        counts.entry(&entry.id).or_default().entry(art).or_default().1 += entry.quantity;
+    }
+
+    counts
+        .into_iter()
+        .filter_map(|(id, arts)| {
+            let base = base_art(id, card_database);
+            let arts: Vec<ArtDiff> = arts
+                .into_iter()
+                .filter(|(_, (before, after))| before != after)
+                .map(|(art, (before, after))| ArtDiff {
+                    art: art.to_string(),
+                    base: art == base,
+                    before,
+                    after,
+                })
+                .collect();
+            if arts.is_empty() {
+                return None;
+            }
+
+            Some(CardDiff {
+                id: id.to_string(),
+                before: before.iter().filter(|e| e.id == id).map(|e| e.quantity).sum(),
+                after: after.iter().filter(|e| e.id == id).map(|e| e.quantity).sum(),
+                arts,
+            })
+        })
+        .collect()
+}
+
+// Cards the card database doesn't have are assumed to start at `P0`.
+fn base_art<'a>(id: &str, card_database: &'a CardDatabase) -> &'a str {
+    card_database.get(id, "").map_or("P0", |card| card.art.as_str())
+}
+
+fn art<'a>(entry: &'a DeckListEntry, card_database: &'a CardDatabase) -> &'a str {
+    if entry.art.is_empty() {
+        base_art(&entry.id, card_database)
+    } else {
+        &entry.art
+    }
+}
+
+pub fn diff_deck_lists(
+    before: &str,
+    after: &str,
+    card_database: &CardDatabase,
+) -> Result<Vec<CardDiff>, Vec<DeckDiagnostic>> {
+    Ok(diff_deck_list_entries(
+        &read_deck_list_entries(before)?,
+        &read_deck_list_entries(after)?,
+        card_database,
+    ))
+}
+
+/// Named revisions of one deck, oldest first, kept in a JSON file next to the deck.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeckHistory {
+    pub revisions: Vec<DeckRevision>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeckRevision {
+    pub name: String,
+    pub entries: Vec<DeckListEntry>,
+}
+
+impl DeckHistory {
+    /// Where the history of the deck file at `deck_path` is kept.
+    pub fn path_for(deck_path: &str) -> String {
+        format!("{deck_path}.history.json")
+    }
+
+    /// Loads a history, or starts an empty one if there is no file yet.
+    pub fn load(path: &str) -> Result<DeckHistory, CardDataError> {
+        let in_file = |e: CardDataError| CardDataError::InFile(path.to_string(), Box::new(e));
+
+        let history = match std::fs::read_to_string(path) {
+            Ok(history) => history,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(DeckHistory::default()),
+            Err(e) => return Err(in_file(CardDataError::Io(e.to_string()))),
+        };
+        serde_json::from_str(&history).map_err(|e| in_file(CardDataError::Json(e.to_string())))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), CardDataError> {
+        let history = serde_json::to_string_pretty(self).unwrap();
+        std::fs::write(path, history + "\n")
+            .map_err(|e| CardDataError::InFile(path.to_string(), Box::new(CardDataError::Io(e.to_string()))))
+    }
+
+    /// Adds a revision after the others. Names have to be unique within a history.
+    pub fn add(&mut self, name: &str, entries: Vec<DeckListEntry>) -> Result<(), String> {
+        if self.get(name).is_some() {
+            return Err(format!("there is already a revision named `{name}`"));
+        }
+
+        self.revisions.push(DeckRevision {
+            name: name.to_string(),
+            entries,
+        });
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DeckRevision> {
+        self.revisions.iter().find(|revision| revision.name == name)
+    }
+
+    pub fn latest(&self) -> Option<&DeckRevision> {
+        self.revisions.last()
+    }
+
+    /// The changes from revision `from` to revision `to`.
+    pub fn diff(&self, from: &str, to: &str, card_database: &CardDatabase) -> Result<Vec<CardDiff>, String> {
+        let revision = |name: &str| self.get(name).ok_or(format!("there is no revision named `{name}`"));
+        Ok(diff_deck_list_entries(&revision(from)?.entries, &revision(to)?.entries, card_database))
+    }
+}
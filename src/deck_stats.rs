@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use super::{card::*, game::*};
+
+/// The numbers deck builders look at for a main deck, computed from the printed cards.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeckStats {
+    pub leader: String,
+    pub cards: usize,
+    pub categories: BTreeMap<String, usize>,
+    pub cost_curve: BTreeMap<i32, usize>,
+    pub counter_total: i32,
+    pub counters_1000: usize,
+    pub counters_2000: usize,
+    pub counter_density: f64, // Share of the main deck with a counter, from 0 to 1.
+    pub blockers: usize,
+    pub triggers: usize,
+    pub types: BTreeMap<String, usize>,
+    pub colors: BTreeMap<String, usize>,
+    pub power: BTreeMap<i32, usize>, // Only cards that have power, i.e. Characters.
+}
+
+impl DeckStats {
+    pub fn new(leader: &Card, main_deck: &Deck) -> DeckStats {
+        let mut stats = DeckStats {
+            leader: format!("{} {}", leader.identifier, leader.name),
+            cards: main_deck.len(),
+            categories: BTreeMap::new(),
+            cost_curve: BTreeMap::new(),
+            counter_total: 0,
+            counters_1000: 0,
+            counters_2000: 0,
+            counter_density: 0.0,
+            blockers: 0,
+            triggers: 0,
+            types: BTreeMap::new(),
+            colors: BTreeMap::new(),
+            power: BTreeMap::new(),
+        };
+
+        let mut with_counter = 0;
+        for card in main_deck.iter() {
+            *stats.categories.entry(card.category.to_string()).or_default() += 1;
+            *stats.cost_curve.entry(card.cost.0).or_default() += 1;
+
+            if let Some(CounterPower(counter)) = card.counter_power {
+                if counter > 0 {
+                    with_counter += 1;
+                    stats.counter_total += counter;
+                }
+                match counter {
+                    1000 => stats.counters_1000 += 1,
+                    2000 => stats.counters_2000 += 1,
+                    _ => {}
+                }
+            }
+
+            if card.has_keyword(Keyword::Blocker) {
+                stats.blockers += 1;
+            }
+            if card.has_trigger() {
+                stats.triggers += 1;
+            }
+            for card_type in card.types.iter() {
+                *stats.types.entry(card_type.clone()).or_default() += 1;
+            }
+            for color in card.color.iter() {
+                *stats.colors.entry(format!("{color:?}")).or_default() += 1;
+            }
+            if let Some(CardPower(power)) = card.power {
+                *stats.power.entry(power).or_default() += 1;
+            }
+        }
+
+        if !main_deck.is_empty() {
+            stats.counter_density = with_counter as f64 / main_deck.len() as f64;
+        }
+
+        stats
+    }
+}
+
+// A plain text report, one table per statistic with a bar for each row.
+impl fmt::Display for DeckStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Leader: {}", self.leader)?;
+        writeln!(f, "Main deck: {} cards", self.cards)?;
+        writeln!(
+            f,
+            "Counters: {} total, {} x1000, {} x2000, {:.0}% of the deck",
+            self.counter_total,
+            self.counters_1000,
+            self.counters_2000,
+            self.counter_density * 100.0
+        )?;
+        writeln!(f, "Blockers: {}", self.blockers)?;
+        writeln!(f, "Triggers: {}", self.triggers)?;
+
+        write_table(f, "Category", &self.categories)?;
+        write_table(f, "Cost", &self.cost_curve)?;
+        write_table(f, "Power", &self.power)?;
+        write_table(f, "Color", &self.colors)?;
+        write_table(f, "Type", &self.types)?;
+
+        Ok(())
+    }
+}
+
+fn write_table<K: fmt::Display>(f: &mut fmt::Formatter<'_>, title: &str, rows: &BTreeMap<K, usize>) -> fmt::Result {
+    let labels: Vec<String> = rows.keys().map(|key| key.to_string()).collect();
+    let width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0).max(title.len());
+
+    writeln!(f)?;
+    writeln!(f, "{title:<width$} | Cards")?;
+    writeln!(f, "{}-+-{}", "-".repeat(width), "-".repeat(5))?;
+    for (label, count) in labels.iter().zip(rows.values()) {
+        writeln!(f, "{label:<width$} | {count:>5} {}", "#".repeat(*count))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use log::*;
+use serde::{Deserialize, Serialize};
+
+use super::{card::*, game::*, script::*, utils::*};
+
+pub const FORMAT_DIR: &str = "assets/formats";
+
+/// The rules a deck is built under on top of the comprehensive rules, loaded from
+/// `assets/formats/{name}.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Format {
+    pub name: String,
+    #[serde(default = "default_max_copies")]
+    pub max_copies: i32, // Copies of any one identifier allowed in the main deck.
+    #[serde(default)]
+    pub min_block: i32, // Cards from older blocks have rotated out. 0 allows every block.
+    #[serde(default)]
+    pub banned: Vec<String>,
+    #[serde(default)]
+    pub restricted: BTreeMap<String, i32>, // Identifier and the copies allowed, e.g. 1.
+    #[serde(default)]
+    pub errata: BTreeMap<String, Errata>,
+}
+
+fn default_max_copies() -> i32 {
+    4
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format {
+            name: "standard".to_string(),
+            max_copies: default_max_copies(),
+            min_block: 0,
+            banned: vec![],
+            restricted: BTreeMap::new(),
+            errata: BTreeMap::new(),
+        }
+    }
+}
+
+/// Official changes to a printed card. Only the fields that are set replace the printed ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Errata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counter_power: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>, // Replaces every effect on the card.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl Format {
+    pub fn load(path: &str) -> Result<Format, CardDataError> {
+        let in_file = |e: CardDataError| CardDataError::InFile(path.to_string(), Box::new(e));
+
+        let format = std::fs::read_to_string(path).map_err(|e| in_file(CardDataError::Io(e.to_string())))?;
+        let format: Format =
+            serde_json::from_str(&format).map_err(|e| in_file(CardDataError::Json(e.to_string())))?;
+
+        format.validate().map_err(in_file)?;
+        Ok(format)
+    }
+
+    /// Checks every errata script up front, so a broken one can't be skipped in a game.
+    /// `load` already does this; formats built in code should call it themselves.
+    pub fn validate(&self) -> Result<(), CardDataError> {
+        for (identifier, errata) in self.errata.iter() {
+            if let Some(script) = &errata.script {
+                parse_script(script).map_err(|e| {
+                    CardDataError::InFile(format!("errata for {identifier}"), Box::new(CardDataError::Script(e)))
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Loads `{name}.json` from `dir`.
+    pub fn load_named(dir: &str, name: &str) -> Result<Format, CardDataError> {
+        Self::load(&format!("{dir}/{name}.json"))
+    }
+
+    /// How many copies of `identifier` this format allows in a deck. Banned cards allow none.
+    pub fn copy_limit(&self, identifier: &str) -> i32 {
+        if self.is_banned(identifier) {
+            return 0;
+        }
+        match self.restricted.get(identifier) {
+            Some(limit) => *limit,
+            None => self.max_copies,
+        }
+    }
+
+    pub fn is_banned(&self, identifier: &str) -> bool {
+        self.banned.iter().any(|banned| banned == identifier)
+    }
+
+    pub fn is_rotated_out(&self, card: &Card) -> bool {
+        // cards without a known block, like DON!!, never rotate.
+        card.block > 0 && card.block < self.min_block
+    }
+
+    /// `card` as it is played in this format, with any errata applied.
+    pub fn apply_errata(&self, card: &Card) -> Card {
+        let mut card = card.clone();
+        let Some(errata) = self.errata.get(&card.identifier) else {
+            return card;
+        };
+
+        if let Some(cost) = errata.cost {
+            card.cost = CardCost(cost);
+        }
+        if let Some(power) = errata.power {
+            card.power = Some(CardPower(power));
+        }
+        if let Some(counter_power) = errata.counter_power {
+            card.counter_power = Some(CounterPower(counter_power));
+        }
+        if let Some(script) = &errata.script {
+            match parse_script(script) {
+                Ok(effects) => card.effects = effects,
+                // only possible for a format that skipped `validate`; the printed effects are kept.
+                Err(e) => error!("errata for {} has an invalid script, {e}", card.identifier),
+            }
+        }
+        if let Some(text) = &errata.text {
+            card.text = text.clone();
+        }
+
+        card
+    }
+}
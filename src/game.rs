@@ -10,9 +10,9 @@ use tokio_serde::formats::*;
 use tokio_serde::Framed;
 use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
 
-use super::{card::*, player::*, player_area::*, *};
+use super::{card::*, card_database::*, deck_formats::*, format::*, player::*, player_area::*, *};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Turn {
     P1,
     P2,
@@ -61,6 +61,31 @@ impl<'stream> PlayerClient<'stream> {
             }
         }
     }
+
+    /// Asks the player for their deck until they report one that is legal in `format`.
+    pub async fn receive_deck(&mut self, card_database: &CardDatabase, format: &Format) -> (Card, Deck, Deck) {
+        loop {
+            self.send_message(ServerMessage::RequestDeck).await;
+            let PlayerAction::ReportDeck(deck_list) = self.receive_next_nonidle_action().await else {
+                continue;
+            };
+
+            match review_deck(&deck_list, card_database, format) {
+                Ok(deck) => return deck,
+                Err(rejection) => {
+                    info!("Rejected a deck.");
+                    self.send_message(rejection).await;
+                }
+            }
+        }
+    }
+}
+
+/// The server's answer to a reported deck list: the deck when it is legal in `format`, or the
+/// `DeckRejected` message to send back. Any deck list format is accepted.
+pub fn review_deck(deck_list: &str, card_database: &CardDatabase, format: &Format) -> Result<(Card, Deck, Deck), ServerMessage> {
+    import_deck_list(deck_list, card_database, format)
+        .map_err(|diagnostics| ServerMessage::DeckRejected(diagnostics.into_iter().map(|d| d.error).collect()))
 }
 
 #[derive(Debug)]
@@ -82,6 +107,10 @@ pub struct GameState {
     pub p2_active_don_area: Deck,
     pub p1_rested_don_area: Deck,
     pub p2_rested_don_area: Deck,
+    pub p1_leader_rested: bool,
+    pub p2_leader_rested: bool,
+    pub p1_lost: bool, // Set when the player takes damage with no life cards left.
+    pub p2_lost: bool,
     pub rng: StdRng,
 }
 
@@ -323,6 +352,10 @@ impl GameState {
             p2_active_don_area: Deck::new(),
             p1_rested_don_area: Deck::new(),
             p2_rested_don_area: Deck::new(),
+            p1_leader_rested: false,
+            p2_leader_rested: false,
+            p1_lost: false,
+            p2_lost: false,
             rng,
         }
     }
@@ -330,6 +363,12 @@ impl GameState {
     pub fn check_loser(&self) -> Option<PlayerId> {
         // Return `None` if there is no loser, otherwise
         // return which of `P1` or `P2` lost.
+        if self.p1_lost {
+            return Some(PlayerId::P1);
+        } else if self.p2_lost {
+            return Some(PlayerId::P2);
+        }
+
         let p1_deck_len = self.player_1.main_deck.len();
         let p2_deck_len = self.player_2.main_deck.len();
 
@@ -346,10 +385,6 @@ impl GameState {
         None
     }
 
-    pub fn trigger_loser() -> Option<PlayerId> {
-        todo!()
-    }
-
     pub fn split_into_player_areas(self) -> (PlayerArea, PlayerArea, TurnInfo, StdRng) {
         match self.turn {
             Turn::P1 => (
@@ -361,6 +396,8 @@ impl GameState {
                     rested_character: self.p1_rested_character_area,
                     active_don: self.p1_active_don_area,
                     rested_don: self.p1_rested_don_area,
+                    leader_rested: self.p1_leader_rested,
+                    lost: self.p1_lost,
                 },
                 PlayerArea {
                     player: self.player_2,
@@ -370,6 +407,8 @@ impl GameState {
                     rested_character: self.p2_rested_character_area,
                     active_don: self.p2_active_don_area,
                     rested_don: self.p2_rested_don_area,
+                    leader_rested: self.p2_leader_rested,
+                    lost: self.p2_lost,
                 },
                 TurnInfo {
                     turn: self.turn,
@@ -387,6 +426,8 @@ impl GameState {
                     rested_character: self.p2_rested_character_area,
                     active_don: self.p2_active_don_area,
                     rested_don: self.p2_rested_don_area,
+                    leader_rested: self.p2_leader_rested,
+                    lost: self.p2_lost,
                 },
                 PlayerArea {
                     player: self.player_1,
@@ -396,6 +437,8 @@ impl GameState {
                     rested_character: self.p1_rested_character_area,
                     active_don: self.p1_active_don_area,
                     rested_don: self.p1_rested_don_area,
+                    leader_rested: self.p1_leader_rested,
+                    lost: self.p1_lost,
                 },
                 TurnInfo {
                     turn: self.turn,
@@ -430,11 +473,25 @@ impl GameState {
             p1_active_don_area: player_1_area.active_don,
             p2_active_don_area: player_2_area.active_don,
             p1_rested_don_area: player_1_area.rested_don,
+            p1_leader_rested: player_1_area.leader_rested,
+            p1_lost: player_1_area.lost,
             p2_rested_don_area: player_2_area.rested_don,
+            p2_leader_rested: player_2_area.leader_rested,
+            p2_lost: player_2_area.lost,
             rng,
         }
     }
 
+    /// Rebuilds continuous effects on both boards. Call this whenever either board changes;
+    /// the current player area always belongs to the player whose turn it is.
+    pub fn recompute_static_effects(
+        current_player_area: &mut PlayerArea,
+        other_player_area: &mut PlayerArea,
+    ) {
+        current_player_area.recompute_static_effects(true);
+        other_player_area.recompute_static_effects(false);
+    }
+
     pub fn public_playfield_state(
         turn_info: TurnInfo,
         current_player_area: &PlayerArea,
@@ -590,12 +647,15 @@ impl GameState {
         current_player_area
             .character
             .append(&mut current_player_area.rested_character);
+        current_player_area.leader_rested = false;
         for card in current_player_area.character.iter_mut() {
             current_player_area
                 .active_don
                 .append(&mut card.attached_don);
+            card.played_this_turn = false;
         }
 
+        Self::recompute_static_effects(&mut current_player_area, &mut other_player_area);
         let public_state =
             Self::public_playfield_state(turn_info, &current_player_area, &other_player_area);
 
@@ -628,6 +688,7 @@ impl GameState {
             }
         }
 
+        Self::recompute_static_effects(&mut current_player_area, &mut other_player_area);
         let public_state =
             Self::public_playfield_state(turn_info, &current_player_area, &other_player_area);
 
@@ -655,6 +716,7 @@ impl GameState {
         current_player_area.player = p;
         current_player_area.active_don.append(&mut drawn_don);
 
+        Self::recompute_static_effects(&mut current_player_area, &mut other_player_area);
         let public_state = Self::public_playfield_state(
             turn_info,
             &current_player_area,
@@ -682,6 +744,7 @@ impl GameState {
     ) -> (PlayerArea, PlayerArea, TurnPhase) {
         debug!("(TURN) [MAIN]");
 
+        Self::recompute_static_effects(&mut current_player_area, &mut other_player_area);
         let public_state = Self::public_playfield_state(
             turn_info,
             &current_player_area,
@@ -712,10 +775,10 @@ impl GameState {
                 return (current_player_area, other_player_area, TurnPhase::End);
             }
             PlayerAction::MainPlayCard(c) => {
-                let card = current_player_area.player.hand.remove(c);
+                let mut card = current_player_area.player.hand.remove(c);
 
-                // Can you pay for it?
-                let cost = card.cost.0;
+                // Can you pay for it? Continuous effects may have changed the printed cost.
+                let cost = card.current_cost().0;
                 if cost as usize > current_player_area.active_don.len() {
                     current_player_client
                         .send_message(ServerMessage::InsufficientDon)
@@ -772,7 +835,7 @@ impl GameState {
                                                         Ok(p) => {current_player_area.player = p;},
                                                         Err(p) => {
                                                             current_player_area.player = p;
-                                                            Self::trigger_loser();
+                                                            current_player_area.lost = true;
                                                         }
                                                     }
                                                 }
@@ -800,8 +863,9 @@ impl GameState {
                                                                     continue;
                                                                 }
 
-                                                                other_player_area = other_player_area.process_knock_out(i);
+                                                                other_player_area = Self::knock_out(other_player_area, i, other_player_client).await;
 
+                                                                Self::recompute_static_effects(&mut current_player_area, &mut other_player_area);
                                                                 let public_state = Self::public_playfield_state(turn_info, &current_player_area, &other_player_area);
 
                                                                 Self::sync_data(
@@ -951,13 +1015,17 @@ impl GameState {
                                 current_player_client.receive_next_nonidle_action().await;
                             match discarded_character {
                                 PlayerAction::DiscardCharacter(i) => {
-                                    let discarded_card =
-                                        current_player_area.character.remove(i);
-                                    current_player_area.player.trash.push(discarded_card);
+                                    current_player_area = Self::leave_field(
+                                        current_player_area,
+                                        i,
+                                        current_player_client,
+                                    )
+                                    .await;
                                 }
                                 _ => unreachable!(),
                             }
                         }
+                        card.played_this_turn = true;
                         current_player_area.character.push(card);
                     }
                     _ => unreachable!(),
@@ -967,6 +1035,60 @@ impl GameState {
                 // first, are we activating the leader or a character card?
                 // then, can you pay for it?
             }
+            PlayerAction::MainBattle(c) => {
+                // neither player can attack during their first turn.
+                if turn_info.turn_n <= 2 {
+                    current_player_client.send_message(ServerMessage::CannotAttack).await;
+                    return (current_player_area, other_player_area, TurnPhase::Main);
+                }
+
+                let attacker = match c {
+                    'l' if current_player_area.leader_rested => {
+                        current_player_client.send_message(ServerMessage::CannotAttack).await;
+                        return (current_player_area, other_player_area, TurnPhase::Main);
+                    }
+                    'l' => {
+                        current_player_area.leader_rested = true;
+                        current_player_area.player.leader.clone()
+                    }
+                    _ => match c.to_digit(10).map(|i| i as usize) {
+                        Some(i) if i < current_player_area.character.len() => {
+                            if !current_player_area.character[i].can_attack() {
+                                current_player_client.send_message(ServerMessage::CannotAttack).await;
+                                return (current_player_area, other_player_area, TurnPhase::Main);
+                            }
+                            let card = current_player_area.character.remove(i);
+                            current_player_area.rested_character.push(card.clone());
+                            card
+                        }
+                        _ => {
+                            current_player_client.send_message(ServerMessage::InvalidTarget).await;
+                            return (current_player_area, other_player_area, TurnPhase::Main);
+                        }
+                    },
+                };
+
+                // only the opposing Leader can be attacked so far, and there are no blocks or counters yet.
+                Self::battle_attack_step().await;
+                Self::battle_block_step().await;
+                Self::battle_counter_step().await;
+
+                let attacker_power = attacker.current_power().map_or(0, |p| p.0);
+                let defender_power = other_player_area.player.leader.current_power().map_or(0, |p| p.0);
+                if attacker_power >= defender_power {
+                    (current_player_area, other_player_area) = Self::battle_damage_step(
+                        &attacker,
+                        current_player_area,
+                        other_player_area,
+                        current_player_client,
+                        other_player_client,
+                        turn_info,
+                    )
+                    .await;
+                }
+
+                Self::battle_end().await;
+            }
             PlayerAction::NoAction => {
                 return (current_player_area, other_player_area, TurnPhase::Main);
             }
@@ -978,13 +1100,244 @@ impl GameState {
         (current_player_area, other_player_area, TurnPhase::Main)
     }
 
+    /// Gives the owner of `player_area` the chance to apply one of the replacement effects on
+    /// their board that stands in for one of `events`. `affected` is the index of the Character the
+    /// events happen to, or `None` for events that happen to the Leader.
+    /// Returns whether a replacement was applied, in which case the default action is skipped.
+    pub async fn offer_replacement_effect<'stream>(
+        mut player_area: PlayerArea,
+        events: &[Replaceable],
+        affected: Option<usize>,
+        owner_client: &mut PlayerClient<'stream>,
+    ) -> (PlayerArea, bool) {
+        let candidates = player_area.replacement_effects(events, affected);
+        if candidates.is_empty() {
+            return (player_area, false);
+        }
+
+        owner_client
+            .send_message(ServerMessage::QueryReplacementEffect(candidates.clone()))
+            .await;
+        let response = owner_client.receive_next_nonidle_action().await;
+        match response {
+            PlayerAction::ApplyReplacementEffect(i) if i < candidates.len() => {
+                if let Effect::Replacement(_, cost, effects) = &candidates[i] {
+                    player_area = player_area.pay_effect_cost(*cost);
+                    // `replacement_effects` only offers replacements that draw, so far.
+                    for effect in effects.iter() {
+                        if let Effect::Draw(n) = *effect {
+                            let res = player_area.player.draw(n);
+                            match res {
+                                Ok(p) => player_area.player = p,
+                                Err(p) => {
+                                    player_area.player = p;
+                                    player_area.lost = true;
+                                }
+                            }
+                        }
+                    }
+                }
+                (player_area, true)
+            }
+            _ => (player_area, false),
+        }
+    }
+
+    /// K.O.s the Character at index `i`, unless a replacement effect is applied instead. Being
+    /// K.O.'d also removes it from the field, so both kinds of replacement are offered at once.
+    pub async fn knock_out<'stream>(
+        player_area: PlayerArea,
+        i: usize,
+        owner_client: &mut PlayerClient<'stream>,
+    ) -> PlayerArea {
+        let (player_area, replaced) = Self::offer_replacement_effect(
+            player_area,
+            &[Replaceable::KnockOut, Replaceable::LeaveField],
+            Some(i),
+            owner_client,
+        )
+        .await;
+        if replaced {
+            return player_area;
+        }
+
+        player_area.process_knock_out(i)
+    }
+
+    /// Trashes the Character at index `i` without K.O.ing it, unless a replacement effect is applied instead.
+    pub async fn leave_field<'stream>(
+        player_area: PlayerArea,
+        i: usize,
+        owner_client: &mut PlayerClient<'stream>,
+    ) -> PlayerArea {
+        let (player_area, replaced) = Self::offer_replacement_effect(
+            player_area,
+            &[Replaceable::LeaveField],
+            Some(i),
+            owner_client,
+        )
+        .await;
+        if replaced {
+            return player_area;
+        }
+
+        player_area.process_leave_field(i)
+    }
+
     pub async fn battle_attack_step() {}
 
     pub async fn battle_block_step() {}
 
     pub async fn battle_counter_step() {}
 
-    pub async fn battle_damage_step() {}
+    pub async fn battle_damage_step<'stream>(
+        attacker: &Card,
+        mut current_player_area: PlayerArea,
+        mut other_player_area: PlayerArea,
+        current_player_client: &mut PlayerClient<'stream>,
+        other_player_client: &mut PlayerClient<'stream>,
+        turn_info: TurnInfo,
+    ) -> (PlayerArea, PlayerArea) {
+        debug!("(BATTLE) [DAMAGE]");
+        let banish = attacker.has_keyword(Keyword::Banish);
+
+        let (p, replaced) = Self::offer_replacement_effect(
+            other_player_area,
+            &[Replaceable::Damage],
+            None,
+            other_player_client,
+        )
+        .await;
+        other_player_area = p;
+        if replaced {
+            return (current_player_area, other_player_area);
+        }
+
+        // with no life cards left this loses the game, which `check_loser` reports after the step.
+        let (p, triggers) = other_player_area.take_life_damage(attacker.damage(), banish);
+        other_player_area = p;
+
+        // the defending player chooses whether to activate each revealed [Trigger]. Triggers that
+        // can't be resolved yet aren't offered, and the card goes to the hand as usual.
+        for card in triggers {
+            if !Self::can_resolve_trigger(&card) {
+                other_player_area.player.hand.push(card);
+                continue;
+            }
+
+            other_player_client
+                .send_message(ServerMessage::QueryActivateTrigger(Box::new(card.clone())))
+                .await;
+            let response = other_player_client.receive_next_nonidle_action().await;
+            match response {
+                PlayerAction::ActivateTrigger => {
+                    (other_player_area, current_player_area) = Self::resolve_trigger(
+                        card,
+                        other_player_area,
+                        current_player_area,
+                        other_player_client,
+                        current_player_client,
+                    )
+                    .await;
+                }
+                _ => other_player_area.player.hand.push(card),
+            }
+        }
+
+        Self::recompute_static_effects(&mut current_player_area, &mut other_player_area);
+        let public_state =
+            Self::public_playfield_state(turn_info, &current_player_area, &other_player_area);
+
+        Self::sync_data(
+            current_player_client,
+            other_player_client,
+            &Box::new(current_player_area.player.clone()),
+            &Box::new(other_player_area.player.clone()),
+            public_state,
+        )
+        .await;
+
+        (current_player_area, other_player_area)
+    }
+
+    /// Whether every effect of the card's [Trigger] is one `resolve_trigger` knows how to carry out.
+    pub fn can_resolve_trigger(card: &Card) -> bool {
+        match card.trigger_effects() {
+            Some(effects) => effects.iter().all(|effect| match effect {
+                Effect::Draw(_) | Effect::KnockOutWithPowerEqualOrLessThan(_) => true,
+                Effect::PlayCard => matches!(card.category, CardCategory::Character),
+                _ => false,
+            }),
+            None => false,
+        }
+    }
+
+    /// Carries out the [Trigger] of a life card revealed by damage. `owner_area` belongs to the
+    /// player who took the damage. The card is trashed afterwards, unless it was played.
+    pub async fn resolve_trigger<'stream>(
+        card: Card,
+        mut owner_area: PlayerArea,
+        mut opponent_area: PlayerArea,
+        owner_client: &mut PlayerClient<'stream>,
+        opponent_client: &mut PlayerClient<'stream>,
+    ) -> (PlayerArea, PlayerArea) {
+        let effects = card.trigger_effects().cloned().unwrap_or_default();
+        let mut played = false;
+        for effect in effects.iter() {
+            match *effect {
+                Effect::Draw(n) => {
+                    let res = owner_area.player.draw(n);
+                    match res {
+                        Ok(p) => owner_area.player = p,
+                        Err(p) => {
+                            owner_area.player = p;
+                            owner_area.lost = true;
+                        }
+                    }
+                }
+                Effect::KnockOutWithPowerEqualOrLessThan(x) => {
+                    let has_target = opponent_area.character.iter().any(|c| c.current_power().map_or(0, |p| p.0) <= x);
+                    if !has_target {
+                        owner_client.send_message(ServerMessage::NoTargetsMeetConditions).await;
+                        continue;
+                    }
+
+                    loop {
+                        owner_client.send_message(ServerMessage::QueryTargetOpposingCharacter).await;
+                        match owner_client.receive_next_nonidle_action().await {
+                            PlayerAction::TargetOpposingCharacter(i)
+                                if opponent_area.character.get(i).is_some_and(|c| c.current_power().map_or(0, |p| p.0) <= x) =>
+                            {
+                                opponent_area = Self::knock_out(opponent_area, i, opponent_client).await;
+                                break;
+                            }
+                            // "up to 1", so the owner can decline.
+                            PlayerAction::NoAction => break,
+                            _ => owner_client.send_message(ServerMessage::InvalidTarget).await,
+                        }
+                    }
+                }
+                Effect::PlayCard => {
+                    if owner_area.character.len() == MAX_CHARACTER_AREA as usize {
+                        owner_client.send_message(ServerMessage::DiscardCharacter).await;
+                        if let PlayerAction::DiscardCharacter(i) = owner_client.receive_next_nonidle_action().await {
+                            owner_area = Self::leave_field(owner_area, i, owner_client).await;
+                        }
+                    }
+                    if owner_area.character.len() < MAX_CHARACTER_AREA as usize {
+                        owner_area.character.push(card.clone());
+                        played = true;
+                    }
+                }
+                _ => {} // not offered, see `can_resolve_trigger`.
+            }
+        }
+
+        if !played {
+            owner_area.player.trash.push(card);
+        }
+        (owner_area, opponent_area)
+    }
 
     pub async fn battle_end() {}
 
@@ -1022,7 +1375,8 @@ pub enum Timing {
     ActivateMain,
     Main, // basically ActivateMain, but for event cards.
     Counter,
-    DuringTurn,
+    DuringTurn, // Continuous during the owner's turn.
+    DuringOpponentsTurn,
     Trigger,
     Always,
 }
@@ -1039,8 +1393,41 @@ pub enum Effect {
     PlayCard,
     PlusPower(i32),
     PlusPowerForBattle(i32),
+    MinusCost(i32),
     Rush,
+    DoubleAttack, // Deals 2 damage to the opponent's life instead of 1.
+    Banish,       // Life cards removed by this card's damage are trashed and their [Trigger] is not activated.
+    YourCharacters(Vec<Effect>), // Continuous effects given to each of the owner's Characters; cost changes also reach Characters in hand.
     TimedEffect(Timing, EffectCost, Vec<Effect>),
+    Replacement(Replaceable, EffectCost, Vec<Effect>), // "If ... would ..., you may pay the cost and do the effects instead."
+}
+
+impl Effect {
+    pub fn keyword(&self) -> Option<Keyword> {
+        match self {
+            Effect::Blocker => Some(Keyword::Blocker),
+            Effect::Rush => Some(Keyword::Rush),
+            Effect::DoubleAttack => Some(Keyword::DoubleAttack),
+            Effect::Banish => Some(Keyword::Banish),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Keyword {
+    Blocker,
+    Rush,
+    DoubleAttack,
+    Banish,
+}
+
+// Events that a `Replacement` effect can stand in for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Replaceable {
+    KnockOut,   // This Character would be K.O.'d.
+    LeaveField, // This Character would be removed from the field by any means.
+    Damage,     // Your Leader would take damage.
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -2,21 +2,38 @@
 
 
 use std::fmt;
+use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
 pub mod card;
+pub mod card_database;
+pub mod card_definition;
+pub mod card_import;
+pub mod card_query;
+pub mod card_text;
+pub mod card_types;
+pub mod card_validation;
+pub mod deck_builder;
+pub mod deck_formats;
+pub mod deck_history;
+pub mod deck_stats;
+pub mod format;
 pub mod game;
+pub mod locale;
 pub mod player;
 pub mod player_area;
+pub mod probability;
+pub mod script;
 
 pub mod utils;
 
 use card::*;
 use game::*;
+use locale::*;
 use player::*;
 
-pub fn print_hand(hand: &Deck) {
+pub fn print_hand(hand: &Deck, messages: &MessageCatalogue) {
     for (i, card) in hand.iter().enumerate() {
         println!("{i}");
 
@@ -25,7 +42,7 @@ pub fn print_hand(hand: &Deck) {
             print!("{}", color);
         }
 
-        println!(" {}", card.name);
+        println!(" {}", card.localised_name(messages.locale));
         println!("{}", card.category);
         if card.types.len() > 0 {
             print!("{}", card.types[0]);
@@ -47,12 +64,17 @@ pub fn print_hand(hand: &Deck) {
             }
         }
         println!();
-        for effect in card.effects.iter() {
-            println!("{} ", effect);
+        match card.translations.get(&messages.locale) {
+            Some(translation) if !translation.text.is_empty() => println!("{} ", translation.text),
+            _ => {
+                for effect in card.effects.iter() {
+                    println!("{} ", effect);
+                }
+            }
         }
 
         match card.counter_power {
-            Some(power) => println!("Counter +{} ", power),
+            Some(power) => println!("{}", messages.format("counter", &[("power", &power.to_string())])),
             None => (),
         };
 
@@ -69,11 +91,13 @@ pub enum PlayerAction {
     MainActivateCardEffect(char),
     MainPlayCard(usize),
     MainAttachDon(usize),
-    MainBattle(usize),
+    MainBattle(char),
     End,
     TargetOpposingCharacter(usize),
     TargetSelfCharacterOrLeader(char),
     DiscardCharacter(usize),
+    ActivateTrigger,
+    ApplyReplacementEffect(usize),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -87,18 +111,23 @@ pub enum ServerMessage {
     QueryMulligan,
     QueryTargetOpposingCharacter,
     QueryTargetSelfCharacterOrLeader,
+    QueryActivateTrigger(Box<Card>),
+    QueryReplacementEffect(Vec<Effect>),
     
     // Data Payloads
     PlayerDataPayload(Box<Player>),
     OtherPlayerDataPayload(Box<Player>),
     PlayerId(Turn),
     PublicPlayfieldStateDataPayload(Box<PublicPlayfieldState>),
+    GameOver(Turn), // The player who won.
 
     // Error Messages
+    DeckRejected(Vec<DeckError>), // The reported deck isn't legal; `RequestDeck` follows.
     CannotPlayCounterEventDuringMainPhase,
     InsufficientDon,
     InvalidTarget,
     NoTargetsMeetConditions,
+    CannotAttack, // First turn, a rested Leader, or a Character played this turn without [Rush].
 }
 
 impl fmt::Display for CardColor {
@@ -118,6 +147,97 @@ impl fmt::Display for CardColor {
     }
 }
 
+// Parsing is case insensitive and ignores spaces, `_` and `-`, and accepts the short
+// codes used by `Display` as well as full names.
+fn normalise_name(s: &str) -> String {
+    s.chars()
+        .filter(|c| !matches!(c, ' ' | '_' | '-'))
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+impl FromStr for CardColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use CardColor::*;
+        match normalise_name(s).as_str() {
+            "red" | "r" => Ok(Red),
+            "blue" | "b" => Ok(Blue),
+            "green" | "g" => Ok(Green),
+            "purple" | "p" => Ok(Purple),
+            "black" | "k" => Ok(Black),
+            "yellow" | "y" => Ok(Yellow),
+            _ => Err(format!("unknown color `{s}`")),
+        }
+    }
+}
+
+// Leaders parse with a life of 0, as the life total can't be known from the name alone.
+impl FromStr for CardCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use CardCategory::*;
+        match normalise_name(s).as_str() {
+            "leader" => Ok(Leader(0)),
+            "character" => Ok(Character),
+            "event" => Ok(Event),
+            "stage" => Ok(Stage),
+            "don" | "don!!" | "don!!card" => Ok(Don),
+            _ => Err(format!("unknown category `{s}`")),
+        }
+    }
+}
+
+impl FromStr for Attribute {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalise_name(s).as_str() {
+            "slash" | "sl" => Ok(Attribute::Slash),
+            "strike" | "st" => Ok(Attribute::Strike),
+            "ranged" | "rn" => Ok(Attribute::Ranged),
+            "special" | "sp" => Ok(Attribute::Special),
+            "wisdom" | "ws" => Ok(Attribute::Wisdom),
+            _ => Err(format!("unknown attribute `{s}`")),
+        }
+    }
+}
+
+impl FromStr for Rarity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalise_name(s).as_str() {
+            "common" | "c" => Ok(Rarity::Common),
+            "uncommon" | "uc" => Ok(Rarity::Uncommon),
+            "rare" | "r" => Ok(Rarity::Rare),
+            "superrare" | "sr" => Ok(Rarity::SuperRare),
+            "secretrare" | "sec" => Ok(Rarity::SecretRare),
+            "leader" | "l" => Ok(Rarity::Leader),
+            "special" | "sp" => Ok(Rarity::Special),
+            "treasurerare" | "tr" => Ok(Rarity::TreasureRare),
+            "promo" | "p" => Ok(Rarity::Promo),
+            _ => Err(format!("unknown rarity `{s}`")),
+        }
+    }
+}
+
+impl FromStr for Keyword {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalise_name(s).as_str() {
+            "blocker" => Ok(Keyword::Blocker),
+            "rush" => Ok(Keyword::Rush),
+            "doubleattack" => Ok(Keyword::DoubleAttack),
+            "banish" => Ok(Keyword::Banish),
+            _ => Err(format!("unknown keyword `{s}`")),
+        }
+    }
+}
+
 impl fmt::Display for CardCost {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)?;
@@ -171,6 +291,23 @@ impl fmt::Display for Attribute {
     }
 }
 
+impl fmt::Display for Rarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Rarity::Common => write!(f, "C")?,
+            Rarity::Uncommon => write!(f, "UC")?,
+            Rarity::Rare => write!(f, "R")?,
+            Rarity::SuperRare => write!(f, "SR")?,
+            Rarity::SecretRare => write!(f, "SEC")?,
+            Rarity::Leader => write!(f, "L")?,
+            Rarity::Special => write!(f, "SP")?,
+            Rarity::TreasureRare => write!(f, "TR")?,
+            Rarity::Promo => write!(f, "P")?,
+        };
+        Ok(())
+    }
+}
+
 impl fmt::Display for Timing {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use Timing::*;
@@ -178,10 +315,11 @@ impl fmt::Display for Timing {
         match self {
             OnPlay => val = "[On Play]".into(),
             WhenAttacking => val = "[When Attacking]".into(),
-            ActivateMain => val = "[Activate:Main]".into(),
+            ActivateMain => val = "[Activate: Main]".into(),
             Main => val = "[Main]".into(),
             Counter => val = "[Counter]".into(),
-            DuringTurn => val = "".into(),
+            DuringTurn => val = "[Your Turn]".into(),
+            DuringOpponentsTurn => val = "[Opponent's Turn]".into(),
             Trigger => val = "[Trigger]".into(),
             Always => val = "".into(),
         }
@@ -206,32 +344,46 @@ impl fmt::Display for EffectCost {
 
 impl fmt::Display for Effect {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        use Effect::*;
+        write!(f, "{}", card_text::effect_text(self))?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use DeckError::*;
         match self {
-            Blocker => write!(f, "<Blocker>")?,
-            TimedEffect(timing, cost, effect) => {
-                write!(f, "{} {} ", timing, cost)?;
-                for effect in effect.iter() {
-                    write!(f, "{} ", effect)?;
-                }
-            },
-            Draw(i) => write!(f, "Draw {}", i)?,
-            GiveOtherCardPower(i) => write!(f, "Give your Leader or 1 of your Characters other than this card +{i} power during this turn.")?,
-            GiveRestedDon(i) => write!(f, "Give this Leader or 1 of your Characters {i} rested DON!! card(s).")?,
-            KnockOutWithPowerEqualOrLessThan(i) => write!(f, "K.O. 1 of your opponent's Characters with a power of {i} or less.")?,
-            OncePerTurn => write!(f, "Once Per Turn")?,
-            OpponentNoBlocker(condition) => {
-                match condition {
-                    Condition::None => write!(f, "Your opponent cannot activate <Blocker> during this battle.")?,
-                    Condition::PowerAndAbove(i) => write!(f, "Your opponent cannot activate <Blocker> of {i} or higher Power Characters during this battle.")?,
-                }
-            },
-            Rush => write!(f, "<Rush>")?,
-            PlayCard => write!(f, "Play this card.")?,
-            PlusPower(i) => write!(f, "+{i}")?,
-            PlusPowerForBattle(i) => write!(f, "Your Leader or 1 of your Characters gains +{i} for this battle.")?,
+            ArtSyntaxError(art) => write!(f, "art `{art}` should be written in parentheses, like (P0)"),
+            NoLeader => write!(f, "the deck has no Leader"),
+            TooManyLeaders => write!(f, "the deck has more than one Leader"),
+            TooManyCopies(id, n) => write!(f, "{n} copies of {id}, more than the format allows"),
+            IncompleteLine(line) => write!(f, "`{line}` needs at least a quantity and a card id"),
+            TooManyFields(line) => write!(f, "`{line}` has too many fields"),
+            InvalidCardId(id) => write!(f, "no card has the id {id}"),
+            InvalidCardName(name) => write!(f, "no card is named {name}"),
+            InvalidCardArt(art) => write!(f, "no card has the art {art}"),
+            InvalidDeckLength(n) => write!(f, "the deck has {n} cards, it needs 61 including the Leader and DON!!"),
+            NotEnoughDon => write!(f, "the deck needs exactly 10 DON!! cards"),
+            BannedCard(id) => write!(f, "{id} is banned"),
+            RestrictedCard(id, n, limit) => write!(f, "{n} copies of {id}, it is restricted to {limit}"),
+            RotatedOut(id, block) => write!(f, "{id} is from block {block}, which has rotated out"),
+            ColorMismatch(id, name) => write!(f, "{id} {name} shares no color with the Leader"),
+            InvalidQuantity(quantity) => write!(f, "`{quantity}` is not a quantity"),
+            UnreadableDeckList(e) => write!(f, "unreadable deck list: {e}"),
+            InvalidDeckCode(e) => write!(f, "invalid deck code: {e}"),
+            NotALeader(id) => write!(f, "{id} is not a Leader"),
+            LeaderInMainDeck(id) => write!(f, "{id} is a Leader and can only be the deck's Leader, not part of the main deck"),
+        }
+    }
+}
+
+impl fmt::Display for DeckDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.error)
+        } else {
+            write!(f, "{}:{}: {}", self.line, self.column, self.error)
         }
-        Ok(())
     }
 }
 
@@ -242,7 +394,7 @@ impl fmt::Display for Card {
             f,
             "| {}                          {} ",
             self.cost,
-            self.power.unwrap_or(CardPower(0))
+            self.current_power().unwrap_or(CardPower(0))
         )?;
         for att in self.attribute.iter() {
             write!(f, "{}", att)?;
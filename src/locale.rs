@@ -0,0 +1,157 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::{card::*, utils::*};
+
+pub const LOCALE_DIR: &str = "assets/locale";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+}
+
+impl Locale {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ja => "ja",
+        }
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "en" | "english" => Ok(Locale::En),
+            "ja" | "jp" | "japanese" => Ok(Locale::Ja),
+            _ => Err(format!("unknown locale `{s}`")),
+        }
+    }
+}
+
+/// A card's printed name and text in a language other than English. Either may be left empty
+/// when it hasn't been translated yet, in which case the English is shown.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CardTranslation {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub text: String,
+}
+
+pub type CardTranslations = BTreeMap<Locale, CardTranslation>;
+
+impl Card {
+    pub fn localised_name(&self, locale: Locale) -> &str {
+        match self.translations.get(&locale) {
+            Some(translation) if !translation.name.is_empty() => &translation.name,
+            _ => &self.name,
+        }
+    }
+
+    pub fn localised_text(&self, locale: Locale) -> &str {
+        match self.translations.get(&locale) {
+            Some(translation) if !translation.text.is_empty() => &translation.text,
+            _ => &self.text,
+        }
+    }
+}
+
+/// The client's UI strings for one locale, loaded from `assets/locale/{code}.json`. Messages
+/// missing from a translation fall back to English, and then to the message key itself.
+#[derive(Debug, Clone, Default)]
+pub struct MessageCatalogue {
+    pub locale: Locale,
+    messages: HashMap<String, String>,
+    fallback: HashMap<String, String>,
+}
+
+impl MessageCatalogue {
+    pub fn load(dir: &str, locale: Locale) -> Result<MessageCatalogue, CardDataError> {
+        let fallback = load_messages(dir, Locale::En)?;
+        let messages = match locale {
+            Locale::En => fallback.clone(),
+            locale => load_messages(dir, locale)?,
+        };
+
+        Ok(MessageCatalogue {
+            locale,
+            messages,
+            fallback,
+        })
+    }
+
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map(|message| message.as_str())
+            .unwrap_or(key)
+    }
+
+    /// A message with each `{name}` placeholder replaced by its value.
+    pub fn format(&self, key: &str, values: &[(&str, &str)]) -> String {
+        let mut message = self.get(key).to_string();
+        for (name, value) in values.iter() {
+            message = message.replace(&format!("{{{name}}}"), value);
+        }
+        message
+    }
+
+    /// The message for a problem with a deck. Values that come from the deck list itself, like
+    /// a card id or an unreadable line, are shown as they were written.
+    pub fn deck_error(&self, error: &DeckError) -> String {
+        use DeckError::*;
+        match error {
+            ArtSyntaxError(art) => self.format("deck_error_art_syntax", &[("art", art)]),
+            NoLeader => self.get("deck_error_no_leader").to_string(),
+            TooManyLeaders => self.get("deck_error_too_many_leaders").to_string(),
+            TooManyCopies(id, n) => self.format("deck_error_too_many_copies", &[("id", id), ("n", &n.to_string())]),
+            IncompleteLine(line) => self.format("deck_error_incomplete_line", &[("line", line)]),
+            TooManyFields(line) => self.format("deck_error_too_many_fields", &[("line", line)]),
+            InvalidCardId(id) => self.format("deck_error_invalid_card_id", &[("id", id)]),
+            InvalidCardName(name) => self.format("deck_error_invalid_card_name", &[("name", name)]),
+            InvalidCardArt(art) => self.format("deck_error_invalid_card_art", &[("art", art)]),
+            InvalidDeckLength(n) => self.format("deck_error_invalid_deck_length", &[("n", &n.to_string())]),
+            NotEnoughDon => self.get("deck_error_not_enough_don").to_string(),
+            BannedCard(id) => self.format("deck_error_banned_card", &[("id", id)]),
+            RestrictedCard(id, n, limit) => self.format(
+                "deck_error_restricted_card",
+                &[("id", id), ("n", &n.to_string()), ("limit", &limit.to_string())],
+            ),
+            RotatedOut(id, block) => self.format("deck_error_rotated_out", &[("id", id), ("block", &block.to_string())]),
+            ColorMismatch(id, name) => self.format("deck_error_color_mismatch", &[("id", id), ("name", name)]),
+            InvalidQuantity(quantity) => self.format("deck_error_invalid_quantity", &[("quantity", quantity)]),
+            UnreadableDeckList(e) => self.format("deck_error_unreadable_deck_list", &[("error", e)]),
+            InvalidDeckCode(e) => self.format("deck_error_invalid_deck_code", &[("error", e)]),
+            NotALeader(id) => self.format("deck_error_not_a_leader", &[("id", id)]),
+            LeaderInMainDeck(id) => self.format("deck_error_leader_in_main_deck", &[("id", id)]),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.messages.keys().map(|key| key.as_str())
+    }
+}
+
+fn load_messages(dir: &str, locale: Locale) -> Result<HashMap<String, String>, CardDataError> {
+    let path = format!("{dir}/{}.json", locale.code());
+    let in_file = |e: CardDataError| CardDataError::InFile(path.clone(), Box::new(e));
+
+    let messages = std::fs::read_to_string(&path).map_err(|e| in_file(CardDataError::Io(e.to_string())))?;
+    serde_json::from_str(&messages).map_err(|e| in_file(CardDataError::Json(e.to_string())))
+}
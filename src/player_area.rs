@@ -8,15 +8,165 @@ pub struct PlayerArea {
     pub rested_character: Deck,
     pub active_don: Deck,
     pub rested_don: Deck,
+    pub leader_rested: bool, // The Leader is rested by attacking, and set active again in the refresh phase.
+    pub lost: bool,
 }
 
 impl PlayerArea {
-    pub fn process_knock_out(mut self, i: usize) -> Self{
+    pub fn process_knock_out(self, i: usize) -> Self{
+        self.process_leave_field(i)
+    }
+
+    pub fn process_leave_field(mut self, i: usize) -> Self {
         let card = self.character.remove(i);
         self.player.trash.push(card);
         self
     }
 
+    /// Replacement effects on this board that stand in for any of `events`, can currently be paid for,
+    /// and only do things `GameState::offer_replacement_effect` can carry out.
+    /// Events that happen to a Character (`affected` is `Some`) are only replaced by that Character's
+    /// own effects, while events that happen to the Leader can be replaced by any card on the board.
+    pub fn replacement_effects(&self, events: &[Replaceable], affected: Option<usize>) -> Vec<Effect> {
+        let sources: Vec<&Card> = match affected {
+            Some(i) => self.character.get(i).into_iter().collect(),
+            None => std::iter::once(&self.player.leader)
+                .chain(self.stage.iter())
+                .chain(self.character.iter())
+                .chain(self.rested_character.iter())
+                .collect(),
+        };
+
+        let mut replacements = vec![];
+        for card in sources {
+            for effect in card.effects.iter() {
+                if let Effect::Replacement(e, cost, effects) = effect {
+                    if events.contains(e)
+                        && self.can_pay_effect_cost(card, *cost)
+                        && effects.iter().all(|effect| matches!(effect, Effect::Draw(_)))
+                    {
+                        replacements.push(effect.clone());
+                    }
+                }
+            }
+        }
+
+        replacements
+    }
+
+    pub fn can_pay_effect_cost(&self, source: &Card, cost: EffectCost) -> bool {
+        match cost {
+            EffectCost::MinusDon(n) => self.count_don_in_play() >= n,
+            EffectCost::RestDon(n) => self.active_don.len() as i32 >= n,
+            EffectCost::DonAttached(n) => source.attached_don.len() as i32 >= n,
+            EffectCost::Zero => true,
+        }
+    }
+
+    /// Pays `cost`, which should already have been checked with `can_pay_effect_cost`.
+    /// DON!! returned to the DON!! deck are taken from the active area first, then the rested area,
+    /// then from those attached to Characters.
+    pub fn pay_effect_cost(mut self, cost: EffectCost) -> Self {
+        match cost {
+            EffectCost::MinusDon(n) => {
+                for _ in 0..n {
+                    let don = self
+                        .active_don
+                        .pop()
+                        .or_else(|| self.rested_don.pop())
+                        .or_else(|| self.character.iter_mut().find_map(|c| c.attached_don.pop()));
+                    match don {
+                        Some(don) => self.player.don_deck.push(don.set_facedown()),
+                        None => break,
+                    }
+                }
+            }
+            EffectCost::RestDon(n) => {
+                for _ in 0..n {
+                    match self.active_don.pop() {
+                        Some(don) => self.rested_don.push(don),
+                        None => break,
+                    }
+                }
+            }
+            EffectCost::DonAttached(_) => {} // a requirement rather than something paid.
+            EffectCost::Zero => {}
+        }
+
+        self
+    }
+
+    /// Deals `n` damage to this player's Leader, one life card at a time. Life cards go to
+    /// the hand, or to the trash when `banish` is set. Unbanished life cards with a [Trigger]
+    /// are handed back instead so their owner can decide whether to activate them.
+    /// Damage taken with no life cards left loses the game instead.
+    pub fn take_life_damage(mut self, n: i32, banish: bool) -> (Self, Deck) {
+        let mut triggers = Deck::new();
+        if self.life.is_empty() {
+            self.lost = true;
+            return (self, triggers);
+        }
+
+        for _ in 0..n {
+            let Some(card) = self.life.pop() else {
+                break;
+            };
+            let card = card.set_faceup();
+
+            if banish {
+                self.player.trash.push(card);
+            } else if card.has_trigger() {
+                triggers.push(card);
+            } else {
+                self.player.hand.push(card);
+            }
+        }
+
+        (self, triggers)
+    }
+
+    /// Rebuilds the continuous effects of every card on this board.
+    pub fn recompute_static_effects(&mut self, owners_turn: bool) {
+        self.player.leader.recompute_static_effects(owners_turn);
+        for card in self
+            .stage
+            .iter_mut()
+            .chain(self.character.iter_mut())
+            .chain(self.rested_character.iter_mut())
+        {
+            card.recompute_static_effects(owners_turn);
+        }
+        for card in self.player.hand.iter_mut() {
+            card.recompute_static_effects(owners_turn);
+        }
+
+        // then the effects cards on the board give to every one of the owner's Characters.
+        let shared: Vec<Status> = std::iter::once(&self.player.leader)
+            .chain(self.stage.iter())
+            .chain(self.character.iter())
+            .chain(self.rested_character.iter())
+            .flat_map(|card| card.active_static_effects(owners_turn))
+            .filter_map(|effect| match effect {
+                Effect::YourCharacters(effects) => Some(statuses(effects)),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        if shared.is_empty() {
+            return;
+        }
+
+        for card in self.character.iter_mut().chain(self.rested_character.iter_mut()) {
+            card.status.extend(shared.iter().copied());
+        }
+        // only a cost change matters to a card that is still in the hand.
+        for card in self.player.hand.iter_mut() {
+            if matches!(card.category, CardCategory::Character) {
+                card.status.extend(shared.iter().filter(|s| matches!(s, Status::CostMinus(_))).copied());
+            }
+        }
+    }
+
     pub fn count_don_in_play(&self) -> i32 {
         self.active_don.len() as i32 + self.rested_don.len() as i32 + self.character.iter().map(|c| c.attached_don.len() as i32).sum::<i32>()
     }
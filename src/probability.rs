@@ -0,0 +1,123 @@
+use rand::prelude::*;
+
+use super::{card::*, player::*};
+
+// Odds of seeing cards by a given turn, following `GameState::setup`: a 5 card opening hand, at
+// most one mulligan that puts the hand back and reshuffles, and life cards taken from the top of
+// the deck after the hand is kept. Life cards are random cards that are never seen, so they make
+// no difference to what is drawn and the exact odds only need the hypergeometric distribution.
+
+pub const OPENING_HAND_SIZE: usize = 5;
+
+/// "At least `at_least` matching cards in hand by `turn`."
+#[derive(Debug, Clone, Copy)]
+pub struct HandQuestion {
+    pub at_least: usize,
+    pub turn: u32,         // The player's own turn, from 1. Turn 1 asks about the opening hand after drawing for turn.
+    pub going_first: bool, // The player going first skips the draw on their first turn.
+    pub mulligan: bool,    // Mulligan any opening hand that doesn't already answer the question.
+    pub life: usize,       // Life cards taken after the hand is kept.
+}
+
+impl Default for HandQuestion {
+    fn default() -> Self {
+        HandQuestion {
+            at_least: 1,
+            turn: 1,
+            going_first: true,
+            mulligan: false,
+            life: 5,
+        }
+    }
+}
+
+impl HandQuestion {
+    /// Cards drawn after the opening hand by the draw step of `turn`.
+    pub fn draws(&self) -> usize {
+        match self.going_first {
+            true => self.turn.saturating_sub(1) as usize,
+            false => self.turn as usize,
+        }
+    }
+}
+
+/// P(X >= `at_least`) when drawing `draws` cards without replacement from `population` cards,
+/// `successes` of which match.
+pub fn hypergeometric_at_least(population: usize, successes: usize, draws: usize, at_least: usize) -> f64 {
+    let draws = draws.min(population);
+    (at_least..=draws.min(successes))
+        .map(|k| {
+            choose(successes, k) * choose(population - successes, draws - k) / choose(population, draws)
+        })
+        .sum()
+}
+
+fn choose(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    let k = k.min(n - k);
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f64 / (i + 1) as f64)
+}
+
+/// The exact odds of `question` for the main deck `deck`, counting cards that `matches`.
+pub fn hand_probability(deck: &Deck, matches: impl Fn(&Card) -> bool, question: &HandQuestion) -> f64 {
+    let population = deck.len();
+    let successes = deck.iter().filter(|card| matches(card)).count();
+    let seen = OPENING_HAND_SIZE + question.draws();
+
+    let answered = hypergeometric_at_least(population, successes, seen, question.at_least);
+    if !question.mulligan {
+        return answered;
+    }
+
+    // a hand that already answers the question is kept, anything else is mulliganed once.
+    let kept = hypergeometric_at_least(population, successes, OPENING_HAND_SIZE, question.at_least);
+    kept + (1.0 - kept) * answered
+}
+
+/// The same odds as `hand_probability`, estimated by dealing `trials` games with `Player`.
+/// No trials estimate nothing, and give `None`.
+pub fn simulate_hand_probability(
+    deck: &Deck,
+    matches: impl Fn(&Card) -> bool,
+    question: &HandQuestion,
+    trials: usize,
+    rng: &mut StdRng,
+) -> Option<f64> {
+    let count_matches = |player: &Player| player.hand.iter().filter(|card| matches(card)).count();
+    let mut answered = 0;
+
+    for _ in 0..trials {
+        let mut player = Player::empty();
+        player.main_deck = deck.clone();
+
+        let Ok(mut player) = player.shuffle(rng).draw(OPENING_HAND_SIZE as i32) else {
+            continue;
+        };
+        if question.mulligan && count_matches(&player) < question.at_least {
+            let Ok(mulliganed) = player.topdeck_hand().shuffle(rng).draw(OPENING_HAND_SIZE as i32) else {
+                continue;
+            };
+            player = mulliganed;
+        }
+
+        let player = match player.draw_out(question.life as i32) {
+            Ok((player, _life)) => player,
+            Err(()) => continue,
+        };
+        let player = match player.draw(question.draws() as i32) {
+            Ok(player) => player,
+            Err(player) => player,
+        };
+
+        if count_matches(&player) >= question.at_least {
+            answered += 1;
+        }
+    }
+
+    if trials == 0 {
+        return None;
+    }
+    Some(answered as f64 / trials as f64)
+}
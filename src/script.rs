@@ -0,0 +1,352 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::game::*;
+
+// A small line based language for writing card effects by hand. Each non-empty line is one `Effect`:
+//
+//     blocker
+//     when_attacking if don>=1: opponent_no_blocker(power>=5000)
+//     activate_main: once_per_turn, give_rested_don(1)
+//     on_play if don-2: draw(2)
+//     instead_of ko if rest_don(1):
+//     your_turn if don>=1: your_characters(plus_power(1000))
+//
+// Lines starting with `#` are comments.
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptError {
+    pub line: usize,   // 1 based.
+    pub column: usize, // 1 based.
+    pub message: String,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(i32),
+    Symbol(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(s) => write!(f, "`{s}`"),
+            Token::Number(n) => write!(f, "`{n}`"),
+            Token::Symbol(s) => write!(f, "`{s}`"),
+        }
+    }
+}
+
+pub fn parse_script(script: &str) -> Result<Vec<Effect>, ScriptError> {
+    let mut effects = vec![];
+
+    for (i, line) in script.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        let tokens = tokenize(line, i + 1)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            line: i + 1,
+            end_column: line.chars().count() + 1,
+        };
+        effects.push(parser.effect_line()?);
+    }
+
+    Ok(effects)
+}
+
+fn tokenize(line: &str, line_number: usize) -> Result<Vec<(Token, usize)>, ScriptError> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let column = i + 1;
+
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let ident: String = chars[start..i].iter().collect();
+            tokens.push((Token::Ident(ident.to_lowercase()), column));
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let digits: String = chars[start..i].iter().collect();
+            let Ok(n) = digits.parse::<i32>() else {
+                return Err(ScriptError {
+                    line: line_number,
+                    column,
+                    message: format!("number `{digits}` is too large"),
+                });
+            };
+            tokens.push((Token::Number(n), column));
+        } else {
+            let symbol = match (c, chars.get(i + 1)) {
+                ('>', Some('=')) => ">=",
+                ('<', Some('=')) => "<=",
+                ('(', _) => "(",
+                (')', _) => ")",
+                (':', _) => ":",
+                (',', _) => ",",
+                ('-', _) => "-",
+                _ => {
+                    return Err(ScriptError {
+                        line: line_number,
+                        column,
+                        message: format!("unexpected character `{c}`"),
+                    })
+                }
+            };
+            i += symbol.len();
+            tokens.push((Token::Symbol(symbol), column));
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    line: usize,
+    end_column: usize,
+}
+
+impl Parser {
+    fn error_at(&self, column: usize, message: String) -> ScriptError {
+        ScriptError {
+            line: self.line,
+            column,
+            message,
+        }
+    }
+
+    fn column(&self) -> usize {
+        match self.tokens.get(self.pos) {
+            Some((_, column)) => *column,
+            None => self.end_column,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn next(&mut self, expected: &str) -> Result<(Token, usize), ScriptError> {
+        match self.tokens.get(self.pos) {
+            Some(token) => {
+                self.pos += 1;
+                Ok(token.clone())
+            }
+            None => Err(self.error_at(
+                self.end_column,
+                format!("expected {expected}, found end of line"),
+            )),
+        }
+    }
+
+    fn ident(&mut self, expected: &str) -> Result<(String, usize), ScriptError> {
+        match self.next(expected)? {
+            (Token::Ident(ident), column) => Ok((ident, column)),
+            (token, column) => Err(self.error_at(column, format!("expected {expected}, found {token}"))),
+        }
+    }
+
+    fn number(&mut self) -> Result<i32, ScriptError> {
+        match self.next("a number")? {
+            (Token::Number(n), _) => Ok(n),
+            (token, column) => Err(self.error_at(column, format!("expected a number, found {token}"))),
+        }
+    }
+
+    fn symbol(&mut self, symbol: &'static str) -> Result<(), ScriptError> {
+        match self.next(&format!("`{symbol}`"))? {
+            (Token::Symbol(s), _) if s == symbol => Ok(()),
+            (token, column) => Err(self.error_at(column, format!("expected `{symbol}`, found {token}"))),
+        }
+    }
+
+    fn eat_symbol(&mut self, symbol: &'static str) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn effect_line(&mut self) -> Result<Effect, ScriptError> {
+        let (word, column) = self.ident("a keyword, timing or `instead_of`")?;
+
+        let effect = if let Some(effect) = keyword(&word) {
+            effect
+        } else if word == "instead_of" {
+            let (event, column) = self.ident("`ko`, `leave_field` or `damage`")?;
+            let event = match event.as_str() {
+                "ko" => Replaceable::KnockOut,
+                "leave_field" => Replaceable::LeaveField,
+                "damage" => Replaceable::Damage,
+                _ => return Err(self.error_at(column, format!("unknown replaceable event `{event}`"))),
+            };
+            let cost = self.cost()?;
+            self.symbol(":")?;
+            Effect::Replacement(event, cost, self.actions(true)?)
+        } else {
+            let timing = match word.as_str() {
+                "on_play" => Timing::OnPlay,
+                "when_attacking" => Timing::WhenAttacking,
+                "activate_main" => Timing::ActivateMain,
+                "main" => Timing::Main,
+                "counter" => Timing::Counter,
+                "your_turn" => Timing::DuringTurn,
+                "opponents_turn" => Timing::DuringOpponentsTurn,
+                "trigger" => Timing::Trigger,
+                "always" => Timing::Always,
+                _ => return Err(self.error_at(column, format!("unknown keyword or timing `{word}`"))),
+            };
+            let cost = self.cost()?;
+            self.symbol(":")?;
+            Effect::TimedEffect(timing, cost, self.actions(false)?)
+        };
+
+        if let Some((token, column)) = self.tokens.get(self.pos) {
+            return Err(self.error_at(*column, format!("unexpected {token} after effect")));
+        }
+
+        Ok(effect)
+    }
+
+    // `if don>=n`, `if don-n` or `if rest_don(n)`, or nothing for `EffectCost::Zero`.
+    fn cost(&mut self) -> Result<EffectCost, ScriptError> {
+        if self.peek() != Some(&Token::Ident("if".into())) {
+            return Ok(EffectCost::Zero);
+        }
+        self.pos += 1;
+
+        let (word, column) = self.ident("`don` or `rest_don`")?;
+        match word.as_str() {
+            "don" => {
+                if self.eat_symbol(">=") {
+                    Ok(EffectCost::DonAttached(self.number()?))
+                } else if self.eat_symbol("-") {
+                    Ok(EffectCost::MinusDon(self.number()?))
+                } else {
+                    Err(self.error_at(self.column(), "expected `>=` or `-` after `don`".into()))
+                }
+            }
+            "rest_don" => {
+                self.symbol("(")?;
+                let n = self.number()?;
+                self.symbol(")")?;
+                Ok(EffectCost::RestDon(n))
+            }
+            _ => Err(self.error_at(column, format!("unknown cost `{word}`"))),
+        }
+    }
+
+    fn actions(&mut self, allow_empty: bool) -> Result<Vec<Effect>, ScriptError> {
+        let mut actions = vec![];
+        if allow_empty && self.peek().is_none() {
+            return Ok(actions);
+        }
+
+        loop {
+            actions.push(self.action()?);
+            if !self.eat_symbol(",") {
+                break;
+            }
+        }
+
+        Ok(actions)
+    }
+
+    fn action(&mut self) -> Result<Effect, ScriptError> {
+        let (word, column) = self.ident("an effect")?;
+        if let Some(effect) = keyword(&word) {
+            return Ok(effect);
+        }
+
+        let effect = match word.as_str() {
+            "once_per_turn" => Effect::OncePerTurn,
+            "play_card" => Effect::PlayCard,
+            "draw" => Effect::Draw(self.argument()?),
+            "give_other_card_power" => Effect::GiveOtherCardPower(self.argument()?),
+            "give_rested_don" => Effect::GiveRestedDon(self.argument()?),
+            "plus_power" => Effect::PlusPower(self.argument()?),
+            "plus_power_for_battle" => Effect::PlusPowerForBattle(self.argument()?),
+            "minus_cost" => Effect::MinusCost(self.argument()?),
+            "ko" => {
+                self.symbol("(")?;
+                self.condition_name("power")?;
+                self.symbol("<=")?;
+                let n = self.number()?;
+                self.symbol(")")?;
+                Effect::KnockOutWithPowerEqualOrLessThan(n)
+            }
+            "your_characters" => {
+                self.symbol("(")?;
+                let effects = self.actions(false)?;
+                self.symbol(")")?;
+                Effect::YourCharacters(effects)
+            }
+            "opponent_no_blocker" => {
+                if self.eat_symbol("(") {
+                    self.condition_name("power")?;
+                    self.symbol(">=")?;
+                    let n = self.number()?;
+                    self.symbol(")")?;
+                    Effect::OpponentNoBlocker(Condition::PowerAndAbove(n))
+                } else {
+                    Effect::OpponentNoBlocker(Condition::None)
+                }
+            }
+            _ => return Err(self.error_at(column, format!("unknown effect `{word}`"))),
+        };
+
+        Ok(effect)
+    }
+
+    fn argument(&mut self) -> Result<i32, ScriptError> {
+        self.symbol("(")?;
+        let n = self.number()?;
+        self.symbol(")")?;
+        Ok(n)
+    }
+
+    fn condition_name(&mut self, name: &str) -> Result<(), ScriptError> {
+        let (word, column) = self.ident(&format!("`{name}`"))?;
+        if word != name {
+            return Err(self.error_at(column, format!("expected `{name}`, found `{word}`")));
+        }
+        Ok(())
+    }
+}
+
+fn keyword(word: &str) -> Option<Effect> {
+    match word {
+        "blocker" => Some(Effect::Blocker),
+        "rush" => Some(Effect::Rush),
+        "double_attack" => Some(Effect::DoubleAttack),
+        "banish" => Some(Effect::Banish),
+        _ => None,
+    }
+}
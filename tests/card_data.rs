@@ -0,0 +1,688 @@
+use optcg::card::*;
+use optcg::card_database::*;
+use optcg::card_definition::*;
+use optcg::card_validation::*;
+use optcg::game::*;
+use optcg::utils::load_card;
+
+// A scratch directory for one test, removed again when it goes out of scope, even if the test fails.
+struct TempDir(String);
+
+impl TempDir {
+    fn new(name: &str) -> TempDir {
+        let dir = std::env::temp_dir().join(format!("optcg-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        TempDir(dir.to_str().unwrap().to_string())
+    }
+
+    fn path(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// The card definitions that used to be hardcoded in `install_card_data`. The JSON in
+// `assets/card_data` is now the source of truth, so these only check that it still matches.
+pub fn hardcoded_cards() -> Vec<Card> {
+    #![allow(non_snake_case)]
+    use Attribute::*;
+    use CardCategory::*;
+    use CardColor::*;
+    use Effect::*;
+    use EffectCost::*;
+    use Facing::*;
+    use Timing::*;
+
+    let DON_don = Card {
+        name: "Your Turn +1000".to_string(),
+        identifier: "DON-don".to_string(),
+        art: "P0".to_string(),
+        cost: CardCost(0),
+        category: CardCategory::Don,
+        power: None,
+        counter_power: None,
+        attribute: vec![],
+        color: vec![],
+        types: vec![],
+        effects: vec![Effect::PlusPower(1000)],
+        text: "[Your Turn] The Leader or Character this card is given to gains +1000 power.".to_string(),
+        set: String::new(),
+        set_name: String::new(),
+        rarity: None,
+        block: 0,
+        release_date: String::new(),
+        translations: Default::default(),
+        attached_don: vec![],
+        status: vec![],
+        facing: FaceDown,
+        played_this_turn: false,
+    };
+
+    let ST01_001 = Card::new(
+        "Monkey D. Luffy".to_string(),
+        "ST01-001".to_string(),
+        "P0".to_string(),
+        CardCost(0),
+        Leader(5),
+        Some(CardPower(5000)),
+        None,
+        vec![Strike],
+        vec![Red],
+        vec!["Supernovas".to_string(), "Straw Hat Crew".to_string()],
+        vec![TimedEffect(
+            ActivateMain,
+            Zero,
+            vec![OncePerTurn, GiveRestedDon(1)],
+        )],
+        FaceDown,
+    )
+    .with_text("[Activate: Main] [Once Per Turn] Give up to 1 rested DON!! card to your Leader or 1 of your Characters.");
+
+    let ST01_002 = Card::new(
+        "Usopp".to_string(),
+        "ST01-002".to_string(),
+        "P0".to_string(),
+        CardCost(2),
+        Character,
+        Some(CardPower(2000)),
+        Some(CounterPower(1000)),
+        vec![Ranged],
+        vec![Red],
+        vec!["Straw Hat Crew".to_string()],
+        vec![
+            TimedEffect(
+                WhenAttacking,
+                DonAttached(1),
+                vec![OpponentNoBlocker(Condition::PowerAndAbove(5000))],
+            ),
+            TimedEffect(Trigger, Zero, vec![PlayCard]),
+        ],
+        FaceDown,
+    )
+    .with_text("[DON!! x1] [When Attacking] Your opponent cannot activate a [Blocker] Character that has 5000 or more power during this battle.\n[Trigger] Play this card.");
+
+    let ST01_003 = Card::new(
+        "Carue".to_string(),
+        "ST01-003".to_string(),
+        "P0".to_string(),
+        CardCost(1),
+        Character,
+        Some(CardPower(3000)),
+        Some(CounterPower(1000)),
+        vec![Strike],
+        vec![Red],
+        vec!["Animal".to_string(), "Alabasta".to_string()],
+        vec![],
+        FaceDown,
+    );
+
+    let ST01_004 = Card::new(
+        "Sanji".to_string(),
+        "ST01-004".to_string(),
+        "P0".to_string(),
+        CardCost(2),
+        Character,
+        Some(CardPower(4000)),
+        None,
+        vec![Strike],
+        vec![Red],
+        vec!["Straw Hat Crew".to_string()],
+        vec![TimedEffect(DuringTurn, DonAttached(2), vec![Rush])],
+        FaceDown,
+    )
+    .with_text("[DON!! x2] [Your Turn] This Character gains [Rush].");
+
+    let ST01_005 = Card::new(
+        "Jinbe".to_string(),
+        "ST01-005".to_string(),
+        "P0".to_string(),
+        CardCost(3),
+        Character,
+        Some(CardPower(5000)),
+        None,
+        vec![Strike],
+        vec![Red],
+        vec!["Fish-Man".to_string(), "Straw Hat Crew".to_string()],
+        vec![TimedEffect(
+            WhenAttacking,
+            DonAttached(1),
+            vec![GiveOtherCardPower(1000)],
+        )],
+        FaceDown,
+    )
+    .with_text("[DON!! x1] [When Attacking] Up to 1 of your Leader or Character cards other than this card gains +1000 power during this turn.");
+
+    let ST01_006 = Card::new(
+        "Tony Tony Chopper".into(),
+        "ST01-006".into(),
+        "P0".into(),
+        CardCost(1),
+        Character,
+        Some(CardPower(1000)),
+        None,
+        vec![Strike],
+        vec![Red],
+        vec!["Animal".into(), "Straw Hat Crew".into()],
+        vec![Blocker],
+        FaceDown,
+    )
+    .with_text("[Blocker]");
+
+    let ST01_007 = Card::new(
+        "Nami".into(),
+        "ST01-007".into(),
+        "P0".into(),
+        CardCost(1),
+        Character,
+        Some(CardPower(1000)),
+        Some(CounterPower(1000)),
+        vec![Special],
+        vec![Red],
+        vec!["Straw Hat Crew".into()],
+        vec![TimedEffect(
+            ActivateMain,
+            Zero,
+            vec![OncePerTurn, GiveRestedDon(1)],
+        )],
+        FaceDown,
+    )
+    .with_text("[Activate: Main] [Once Per Turn] Give up to 1 rested DON!! card to your Leader or 1 of your Characters.");
+
+    let ST01_008 = Card::new(
+        "Nico Robin".to_string(),
+        "ST01-008".to_string(),
+        "P0".to_string(),
+        CardCost(3),
+        Character,
+        Some(CardPower(5000)),
+        Some(CounterPower(1000)),
+        vec![Wisdom],
+        vec![Red],
+        vec!["Straw Hat Crew".to_string()],
+        vec![],
+        FaceDown,
+    );
+
+    let ST01_009 = Card::new(
+        "Nefertari Vivi".to_string(),
+        "ST01-009".to_string(),
+        "P0".to_string(),
+        CardCost(2),
+        Character,
+        Some(CardPower(4000)),
+        Some(CounterPower(1000)),
+        vec![Slash],
+        vec![Red],
+        vec!["Alabasta".to_string()],
+        vec![],
+        FaceDown,
+    );
+
+    let ST01_010 = Card::new(
+        "Franky".to_string(),
+        "ST01-010".to_string(),
+        "P0".to_string(),
+        CardCost(4),
+        Character,
+        Some(CardPower(6000)),
+        Some(CounterPower(1000)),
+        vec![Strike],
+        vec![Red],
+        vec!["Straw Hat Crew".to_string()],
+        vec![],
+        FaceDown,
+    );
+
+    let ST01_011 = Card::new(
+        "Brook".to_string(),
+        "ST01-011".to_string(),
+        "P0".to_string(),
+        CardCost(2),
+        Character,
+        Some(CardPower(3000)),
+        Some(CounterPower(2000)),
+        vec![Slash],
+        vec![Red],
+        vec!["Straw Hat Crew".to_string()],
+        vec![TimedEffect(OnPlay, Zero, vec![GiveRestedDon(2)])],
+        FaceDown,
+    )
+    .with_text("[On Play] Give up to 2 rested DON!! cards to your Leader or 1 of your Characters.");
+
+    let ST01_012 = Card::new(
+        "Monkey D. Luffy".to_string(),
+        "ST01-012".to_string(),
+        "P0".to_string(),
+        CardCost(5),
+        Character,
+        Some(CardPower(6000)),
+        None,
+        vec![Strike],
+        vec![Red],
+        vec!["Supernovas".to_string(), "Straw Hat Crew".to_string()],
+        vec![
+            Rush,
+            TimedEffect(
+                WhenAttacking,
+                DonAttached(2),
+                vec![OpponentNoBlocker(Condition::None)],
+            ),
+        ],
+        FaceDown,
+    )
+    .with_text("[Rush]\n[DON!! x2] [When Attacking] Your opponent cannot activate [Blocker] during this battle.");
+
+    let ST01_013 = Card::new(
+        "Roronoa Zoro".to_string(),
+        "ST01-013".to_string(),
+        "P0".to_string(),
+        CardCost(3),
+        Character,
+        Some(CardPower(5000)),
+        None,
+        vec![Slash],
+        vec![Red],
+        vec!["Supernovas".to_string(), "Straw Hat Crew".to_string()],
+        vec![TimedEffect(
+            DuringTurn,
+            DonAttached(1),
+            vec![PlusPower(1000)],
+        )],
+        FaceDown,
+    )
+    .with_text("[DON!! x1] [Your Turn] This Character gains +1000 power.");
+
+    let ST01_014 = Card::new(
+        "Guard Point".to_string(),
+        "ST01-014".to_string(),
+        "P0".to_string(),
+        CardCost(1),
+        Event,
+        None,
+        None,
+        vec![],
+        vec![Red],
+        vec!["Animal".to_string(), "Straw Hat Crew".to_string()],
+        vec![
+            TimedEffect(Counter, Zero, vec![PlusPowerForBattle(3000)]),
+            TimedEffect(Trigger, Zero, vec![PlusPower(1000)]),
+        ],
+        FaceDown,
+    )
+    .with_text("[Counter] Up to 1 of your Leader or Character cards gains +3000 power during this battle.\n[Trigger] Up to 1 of your Leader or Character cards gains +1000 power during this turn.");
+
+    let ST01_015 = Card::new(
+        "Jet Pistol".to_string(),
+        "ST01-015".to_string(),
+        "P0".to_string(),
+        CardCost(4),
+        Event,
+        None,
+        None,
+        vec![],
+        vec![Red],
+        vec!["Supernovas".to_string(), "Straw Hat Crew".to_string()],
+        vec![
+            TimedEffect(Main, Zero, vec![KnockOutWithPowerEqualOrLessThan(6000)]),
+            TimedEffect(Trigger, Zero, vec![KnockOutWithPowerEqualOrLessThan(6000)]),
+        ],
+        FaceDown,
+    )
+    .with_text("[Main] K.O. up to 1 of your opponent's Characters with 6000 power or less.\n[Trigger] K.O. up to 1 of your opponent's Characters with 6000 power or less.");
+
+    let OP09_072 = Card {
+        name: "Franky".to_string(),
+        identifier: "OP09-072".to_string(),
+        art: "P1".to_string(),
+        cost: CardCost(4),
+        category: CardCategory::Character,
+        power: Some(CardPower(5000)),
+        counter_power: Some(CounterPower(1000)),
+        attribute: vec![Attribute::Strike],
+        color: vec![CardColor::Purple],
+        types: vec!["Straw Hat Crew".to_string()],
+        effects: vec![
+            Effect::Blocker,
+            Effect::TimedEffect(
+                Timing::OnPlay,
+                EffectCost::MinusDon(2),
+                vec![Effect::Draw(2)],
+            ),
+        ],
+        text: "[Blocker]\n[On Play] DON!! -2: Draw 2 cards.".to_string(),
+        set: "OP09".to_string(),
+        set_name: "Emperors in the New World".to_string(),
+        rarity: Some(Rarity::Uncommon),
+        block: 3,
+        release_date: "2024-12-13".to_string(),
+        translations: Default::default(),
+        attached_don: vec![],
+        status: vec![],
+        facing: FaceDown,
+        played_this_turn: false,
+    };
+
+    let mut ST01 = vec![
+        ST01_001, ST01_002, ST01_003, ST01_004, ST01_005, ST01_006, ST01_007, ST01_008, ST01_009,
+        ST01_010, ST01_011, ST01_012, ST01_013, ST01_014, ST01_015,
+    ];
+    for card in ST01.iter_mut() {
+        card.set = "ST01".to_string();
+        card.set_name = "Straw Hat Crew".to_string();
+        card.block = 1;
+        card.release_date = "2022-12-02".to_string();
+        card.rarity = match card.identifier.as_str() {
+            "ST01-001" => Some(Rarity::Leader),
+            "ST01-012" | "ST01-013" => Some(Rarity::SuperRare),
+            _ => Some(Rarity::Common),
+        };
+    }
+
+    let mut cards = vec![DON_don];
+    cards.extend(ST01);
+    cards.push(OP09_072);
+    cards
+}
+
+#[test]
+fn hardcoded_cards_round_trip() {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+
+    for card in hardcoded_cards() {
+        let card_data = serde_json::to_string_pretty(&CardDefinition::from_card(&card)).unwrap();
+        let expected = serde_json::to_value(&card).unwrap();
+
+        let reloaded = load_card(&card_data).unwrap();
+        assert_eq!(
+            expected,
+            serde_json::to_value(&reloaded).unwrap(),
+            "{} ({}) does not survive serialisation",
+            card.identifier,
+            card.art
+        );
+
+        // translations were never hardcoded, they only live in the card data.
+        let stored = card_database.get(&card.identifier, &card.art).unwrap();
+        let mut card = card;
+        card.translations = stored.translations.clone();
+        let expected = serde_json::to_value(&card).unwrap();
+        assert_eq!(
+            expected,
+            serde_json::to_value(stored).unwrap(),
+            "{} ({}) differs from its file in {}",
+            card.identifier,
+            card.art,
+            CARD_DATA_DIR
+        );
+    }
+}
+
+#[test]
+fn card_data_is_valid() {
+    let problems = validate_card_data(CARD_DATA_DIR);
+    assert!(
+        problems.is_empty(),
+        "{}",
+        problems.iter().map(|p| p.to_string()).collect::<Vec<String>>().join("\n")
+    );
+}
+
+#[test]
+fn validation_reports_every_problem() {
+    let card_data = r#"{
+        "name": "Bad Leader",
+        "identifier": "ST01-001",
+        "art": "P0",
+        "cost": 3,
+        "category": { "Leader": 0 },
+        "power": 5000,
+        "counter_power": null,
+        "attached_don": [{ "name": "Your Turn +1000" }],
+        "status": [],
+        "facing": "FaceDown"
+    }"#;
+
+    let expected = vec![
+        "runtime field `attached_don` must be empty",
+        "file name should be ST01-001.json",
+        "Leader must have a positive life total, not 0",
+        "Leader must have no cost, not 3",
+        "card from set ST01 must have a rarity",
+    ];
+    assert_eq!(validate_card_file("ST01-001-P1.json", card_data), expected);
+
+    // a whole directory names the file each problem is in, and has none for valid files.
+    let temp_dir = TempDir::new("validate");
+    let dir = temp_dir.path();
+    std::fs::write(format!("{dir}/ST01-001-P1.json"), card_data).unwrap();
+    std::fs::copy(format!("{CARD_DATA_DIR}/ST01-007.json"), format!("{dir}/ST01-007.json")).unwrap();
+    std::fs::write(format!("{dir}/ST01-008.json"), "{ not json").unwrap();
+
+    let problems = validate_card_data(dir);
+
+    let found: Vec<(String, String)> = problems.into_iter().map(|p| (p.file, p.message)).collect();
+    let bad_leader = format!("{dir}/ST01-001-P1.json");
+    let mut expected: Vec<(String, String)> =
+        expected.into_iter().map(|message| (bad_leader.clone(), message.to_string())).collect();
+    expected.push((
+        format!("{dir}/ST01-008.json"),
+        "key must be a string at line 1 column 3".to_string(),
+    ));
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn search_finds_cards_by_name_type_and_text() {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let identifiers = |text: &str| {
+        card_database
+            .search(text)
+            .iter()
+            .map(|card| card.identifier.clone())
+            .collect::<Vec<String>>()
+    };
+
+    assert_eq!(identifiers("nefertari"), vec!["ST01-009"]);
+    assert_eq!(identifiers("alabasta anim"), vec!["ST01-003"]);
+    assert_eq!(identifiers("k.o."), vec!["ST01-015"]);
+    assert!(identifiers("").is_empty());
+}
+
+#[test]
+fn csv_card_list_matches_card_data() {
+    use optcg::card_import::*;
+
+    let card_list = "id,art,name,cost,category,power,counter,attributes,colors,types,life,effect script,rarity
+ST01-001,P0,Monkey D. Luffy,,Leader,5000,,Strike,Red,Supernovas/Straw Hat Crew,5,\"activate_main: once_per_turn, give_rested_don(1)\",L
+ST01-002,P0,Usopp,2,Character,2000,1000,Ranged,Red,Straw Hat Crew,,\"when_attacking if don>=1: opponent_no_blocker(power>=5000)
+trigger: play_card\",C
+ST01-009,P0,Nefertari Vivi,2,Character,,1000,Wisdom,Red,Alabasta,,,C
+ST01-010,P0,Franky,four,Character,6000,,Strike,Red,Straw Hat Crew,,,C
+";
+
+    let (definitions, errors) = import_card_list(card_list, detect_delimiter(card_list));
+    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec!["row 4: Character must have power", "row 5: cost `four` is not a number"]
+    );
+
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    for definition in definitions.iter() {
+        let card = definition.to_card().unwrap();
+        let stored = card_database.get(&card.identifier, &card.art).unwrap();
+        assert_eq!(serde_json::to_value(&card.effects).unwrap(), serde_json::to_value(&stored.effects).unwrap());
+        assert_eq!(card.text, stored.text);
+        assert_eq!(card.rarity, stored.rarity);
+        assert_eq!(card.life(), stored.life());
+    }
+    assert_eq!(definitions.len(), 2);
+}
+
+#[test]
+fn csv_rows_for_other_arts_must_match_the_first_row() {
+    use optcg::card_import::*;
+
+    let card_list = "id,art,name,cost,category,power,counter,attributes,colors,types,life,effect script,rarity
+ST01-009,P0,Nefertari Vivi,2,Character,0,1000,Wisdom,Red,Alabasta,,,C
+ST01-009,P1,Nefertari Vivi,3,Character,0,1000,Wisdom,Red,Alabasta,,,C
+ST01-009,P2,Nefertari Vivi,2,Character,0,1000,Wisdom,Red,Alabasta,,,C
+";
+
+    let (definitions, errors) = import_card_list(card_list, detect_delimiter(card_list));
+    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(errors, vec!["row 3: the arts of ST01-009 have a different cost"]);
+    assert_eq!(definitions.len(), 1);
+    assert_eq!(definitions[0].arts, vec!["P0", "P2"]);
+}
+
+#[test]
+fn migration_merges_art_files_into_one_card() {
+    let temp_dir = TempDir::new("migrate-arts");
+    let dir = temp_dir.path();
+
+    // schema 2 kept each art of a card in its own file.
+    for art in ["P1", "P0"] {
+        old_nami_art(dir, art, |_| {});
+    }
+
+    let upgraded = migrate_card_files(dir).unwrap();
+    let card_database = CardDatabase::load(dir);
+    let files: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_str().unwrap().to_string())
+        .collect();
+    let problems = validate_card_data(dir);
+
+    assert_eq!(upgraded.len(), 1);
+    assert_eq!(files, vec!["ST01-007.json"]);
+    assert!(problems.is_empty(), "{problems:?}");
+
+    let card_database = card_database.unwrap();
+    let arts: Vec<&str> = card_database.arts("ST01-007").iter().map(|card| card.art.as_str()).collect();
+    assert_eq!(arts, vec!["P0", "P1"]);
+    assert_eq!(card_database.get("ST01-007", "").unwrap().art, "P0");
+}
+
+// A schema 2 file for one art of Nami, changed by `edit`.
+fn old_nami_art(dir: &str, art: &str, edit: impl Fn(&mut serde_json::Map<String, serde_json::Value>)) {
+    let nami = std::fs::read_to_string(format!("{CARD_DATA_DIR}/ST01-007.json")).unwrap();
+    let mut card_data: serde_json::Value = serde_json::from_str(&nami).unwrap();
+    let fields = card_data.as_object_mut().unwrap();
+    fields.remove("arts");
+    fields.insert("art".into(), art.into());
+    fields.insert("schema_version".into(), 2.into());
+    edit(fields);
+    std::fs::write(format!("{dir}/ST01-007-{art}.json"), card_data.to_string()).unwrap();
+}
+
+#[test]
+fn migration_keeps_the_base_art_of_an_up_to_date_file() {
+    let temp_dir = TempDir::new("migrate-base-art");
+    let dir = temp_dir.path();
+
+    // `ST01-007-P1.json` sorts before `ST01-007.json`, but the up to date file keeps its base art.
+    std::fs::copy(format!("{CARD_DATA_DIR}/ST01-007.json"), format!("{dir}/ST01-007.json")).unwrap();
+    old_nami_art(dir, "P1", |_| {});
+
+    let upgraded = migrate_card_files(dir).unwrap();
+    let card_database = CardDatabase::load(dir);
+    let files: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_str().unwrap().to_string())
+        .collect();
+
+    assert_eq!(upgraded.len(), 1);
+    assert_eq!(files, vec!["ST01-007.json"]);
+    let card_database = card_database.unwrap();
+    let arts: Vec<&str> = card_database.arts("ST01-007").iter().map(|card| card.art.as_str()).collect();
+    assert_eq!(arts, vec!["P0", "P1"]);
+    assert_eq!(card_database.get("ST01-007", "").unwrap().art, "P0");
+}
+
+#[test]
+fn migration_reports_art_files_that_disagree() {
+    let temp_dir = TempDir::new("migrate-conflict");
+    let dir = temp_dir.path();
+
+    old_nami_art(dir, "P0", |_| {});
+    old_nami_art(dir, "P1", |fields| {
+        fields.insert("cost".into(), 9.into());
+    });
+
+    let error = migrate_card_files(dir).unwrap_err();
+    let mut files: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_str().unwrap().to_string())
+        .collect();
+    files.sort();
+
+    assert_eq!(error.to_string(), format!("{dir}/ST01-007-P1.json: the arts of ST01-007 have a different cost"));
+    // nothing is written when the files can't be merged.
+    assert_eq!(files, vec!["ST01-007-P0.json", "ST01-007-P1.json"]);
+}
+
+#[test]
+fn cards_are_indexed_by_set_rarity_and_block() {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let ids = |cards: Vec<&Card>| -> Vec<String> { cards.iter().map(|card| card.identifier.clone()).collect() };
+
+    assert_eq!(card_database.sets().collect::<Vec<&str>>(), vec!["OP09", "ST01"]);
+    assert_eq!(ids(card_database.in_set("OP09")), vec!["OP09-072"]);
+    assert_eq!(card_database.in_set("ST01").len(), 15);
+    assert!(card_database.in_set("OP01").is_empty());
+
+    assert_eq!(ids(card_database.with_rarity(Rarity::Leader)), vec!["ST01-001"]);
+    assert_eq!(ids(card_database.with_rarity(Rarity::SuperRare)), vec!["ST01-012", "ST01-013"]);
+    assert_eq!(ids(card_database.with_rarity(Rarity::Uncommon)), vec!["OP09-072"]);
+    assert!(card_database.with_rarity(Rarity::SecretRare).is_empty());
+
+    assert_eq!(ids(card_database.in_block(3)), vec!["OP09-072"]);
+    assert_eq!(card_database.in_block(1).len(), 15);
+    // DON!! cards have no block.
+    assert_eq!(ids(card_database.in_block(0)), vec!["DON-don"]);
+    assert!(card_database.in_block(2).is_empty());
+
+    // every art of a card is indexed.
+    let mut card_database = card_database;
+    let mut nami = card_database.get("ST01-007", "").unwrap().clone();
+    nami.art = "P1".to_string();
+    card_database.insert(nami).unwrap();
+    assert_eq!(card_database.in_set("ST01").len(), 16);
+}
+
+#[test]
+fn schema_0_card_dumps_migrate_and_load() {
+    // schema 0 files were a `Card` written out as it was in play, before sets were recorded.
+    let nami = load_card(&std::fs::read_to_string(format!("{CARD_DATA_DIR}/ST01-007.json")).unwrap()).unwrap();
+    let mut card_data = serde_json::to_value(&nami).unwrap();
+    let fields = card_data.as_object_mut().unwrap();
+    for field in ["set", "set_name", "rarity", "block", "release_date", "translations"] {
+        fields.remove(field);
+    }
+    fields.insert("attached_don".into(), serde_json::json!([{ "name": "DON!!" }]));
+    fields.insert("facing".into(), "FaceUp".into());
+    assert!(!fields.contains_key("schema_version"));
+
+    let migrated = migrate(card_data.clone()).unwrap();
+    let fields = migrated.as_object().unwrap();
+    for field in ["attached_don", "status", "facing", "art"] {
+        assert!(!fields.contains_key(field), "{field} should be dropped");
+    }
+    assert_eq!(fields["schema_version"], 3);
+    assert_eq!(fields["arts"], serde_json::json!(["P0"]));
+    assert_eq!(fields["set"], "ST01");
+
+    let card = load_card(&card_data.to_string()).unwrap();
+    assert_eq!(card.identifier, "ST01-007");
+    assert_eq!(card.art, "P0");
+    assert_eq!(card.set, "ST01");
+    assert!(card.attached_don.is_empty());
+    assert!(matches!(card.facing, Facing::FaceDown));
+}
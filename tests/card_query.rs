@@ -0,0 +1,82 @@
+use optcg::card::*;
+use optcg::card_database::*;
+use optcg::card_query::*;
+use optcg::game::*;
+
+// The identifiers of the cards a query made of command line options finds, in order.
+fn find(options: &[(&str, &str)]) -> Vec<String> {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let mut query = CardQuery::default();
+    for (option, value) in options {
+        assert_eq!(query.set_option(option, value), Ok(true), "{option} {value}");
+    }
+
+    let mut ids: Vec<String> = card_database.query(&query).iter().map(|card| card.identifier.clone()).collect();
+    ids.sort();
+    ids
+}
+
+#[test]
+fn each_filter_narrows_the_cards() {
+    assert_eq!(find(&[("--color", "purple")]), vec!["OP09-072"]);
+    assert_eq!(find(&[("--category", "leader")]), vec!["ST01-001"]);
+    assert_eq!(find(&[("--category", "event")]), vec!["ST01-014", "ST01-015"]);
+    assert_eq!(find(&[("--cost", "5")]), vec!["ST01-012"]);
+    assert_eq!(find(&[("--power", "6000")]), vec!["ST01-010", "ST01-012"]);
+    assert_eq!(find(&[("--type", "fish")]), vec!["ST01-005"]);
+    assert_eq!(find(&[("--attribute", "wisdom")]), vec!["ST01-008"]);
+    assert_eq!(find(&[("--set", "op09")]), vec!["OP09-072"]);
+    assert_eq!(find(&[("--rarity", "super_rare")]), vec!["ST01-012", "ST01-013"]);
+    assert_eq!(find(&[("--released-by", "2023-01-01")]).len(), 15);
+    assert!(find(&[("--released-by", "2022-12-01")]).is_empty());
+
+    // keywords count wherever they are, even behind DON!!.
+    assert_eq!(find(&[("--keyword", "rush")]), vec!["ST01-004", "ST01-012"]);
+    assert_eq!(find(&[("--keyword", "blocker")]), vec!["OP09-072", "ST01-006"]);
+
+    // every filter has to match.
+    assert_eq!(find(&[("--type", "straw hat"), ("--attribute", "slash"), ("--cost", "..2")]), vec!["ST01-011"]);
+}
+
+#[test]
+fn ranges_can_leave_out_either_end() {
+    assert_eq!(find(&[("--cost", "4..")]), vec!["OP09-072", "ST01-010", "ST01-012", "ST01-015"]);
+    assert_eq!(find(&[("--power", "..1000")]), vec!["ST01-006", "ST01-007"]);
+    assert_eq!(find(&[("--power", "2000..3000")]), vec!["ST01-002", "ST01-003", "ST01-011"]);
+    assert_eq!(find(&[("--block", "2..")]), vec!["OP09-072"]);
+    // an empty range is no filter at all.
+    assert_eq!(find(&[("--cost", "..")]).len(), 17);
+    // cards without a power never match a power filter.
+    assert!(!find(&[("--power", "0..")]).contains(&"ST01-014".to_string()));
+}
+
+#[test]
+fn short_names_are_accepted() {
+    let mut query = CardQuery::default();
+    query.set_option("--color", "R").unwrap();
+    query.set_option("--attribute", "SL").unwrap();
+    query.set_option("--rarity", "SR").unwrap();
+    query.set_option("--keyword", "double_attack").unwrap();
+    assert_eq!(query.color, Some(CardColor::Red));
+    assert_eq!(query.attribute, Some(Attribute::Slash));
+    assert_eq!(query.rarity, Some(Rarity::SuperRare));
+    assert_eq!(query.keyword, Some(Keyword::DoubleAttack));
+
+    assert_eq!("Double Attack".parse::<Keyword>(), Ok(Keyword::DoubleAttack));
+    assert_eq!("r".parse::<Rarity>(), Ok(Rarity::Rare));
+}
+
+#[test]
+fn bad_options_are_reported() {
+    let mut query = CardQuery::default();
+    assert_eq!(query.set_option("--cost", "two"), Err("`two` is not a number".to_string()));
+    assert_eq!(query.set_option("--power", "1000..lots"), Err("`lots` is not a number".to_string()));
+    assert_eq!(query.set_option("--color", "pink"), Err("unknown color `pink`".to_string()));
+    assert_eq!(query.set_option("--keyword", "flying"), Err("unknown keyword `flying`".to_string()));
+    assert!(query.set_option("--rarity", "mythic").is_err());
+    assert!(query.set_option("--attribute", "magic").is_err());
+    assert!(query.set_option("--category", "spell").is_err());
+
+    // other options are left for the tool.
+    assert_eq!(query.set_option("--json", ""), Ok(false));
+}
@@ -0,0 +1,58 @@
+use optcg::card_text::card_text;
+use optcg::utils::load_card;
+
+// Every card's stored printed text should match the text generated from its effects,
+// which catches effects that were entered incorrectly in the card data.
+#[test]
+fn generated_text_matches_printed_text() {
+    let mut mismatches = vec![];
+
+    for entry in std::fs::read_dir("assets/card_data").unwrap() {
+        let path = entry.unwrap().path();
+        let card_data = std::fs::read_to_string(&path).unwrap();
+        let card = load_card(&card_data).unwrap();
+
+        let generated = card_text(&card);
+        if generated != card.text {
+            mismatches.push(format!(
+                "{}:\n  printed:   {:?}\n  generated: {:?}",
+                path.display(),
+                card.text,
+                generated
+            ));
+        }
+    }
+
+    assert!(mismatches.is_empty(), "{}", mismatches.join("\n"));
+}
+
+// The stored text is itself written by `card_text`, so a few cards covering each kind of
+// effect are also checked against their printed text, typed in by hand.
+#[test]
+fn generated_text_matches_hand_typed_text() {
+    let printed = [
+        // [Activate: Main]
+        (
+            "ST01-001",
+            "[Activate: Main] [Once Per Turn] Give up to 1 rested DON!! card to your Leader or 1 of your Characters.",
+        ),
+        // [DON!! x1] and [Trigger]
+        (
+            "ST01-002",
+            "[DON!! x1] [When Attacking] Your opponent cannot activate a [Blocker] Character that has 5000 or more power during this battle.\n[Trigger] Play this card.",
+        ),
+        // [Your Turn]
+        ("ST01-013", "[DON!! x1] [Your Turn] This Character gains +1000 power."),
+        ("ST01-004", "[DON!! x2] [Your Turn] This Character gains [Rush]."),
+        (
+            "ST01-015",
+            "[Main] K.O. up to 1 of your opponent's Characters with 6000 power or less.\n[Trigger] K.O. up to 1 of your opponent's Characters with 6000 power or less.",
+        ),
+    ];
+
+    for (id, text) in printed {
+        let card_data = std::fs::read_to_string(format!("assets/card_data/{id}.json")).unwrap();
+        let card = load_card(&card_data).unwrap();
+        assert_eq!(card_text(&card), text, "{id}");
+    }
+}
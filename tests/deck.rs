@@ -0,0 +1,526 @@
+use optcg::card::*;
+use optcg::card_database::*;
+use optcg::format::*;
+use optcg::utils::*;
+
+fn sample_deck_list() -> String {
+    std::fs::read_to_string("sample_deck.txt").unwrap()
+}
+
+fn standard() -> Format {
+    Format::load_named(FORMAT_DIR, "standard").unwrap()
+}
+
+fn errors(result: Result<(Card, Deck, Deck), Vec<DeckDiagnostic>>) -> Vec<DeckError> {
+    match result {
+        Ok(_) => vec![],
+        Err(diagnostics) => diagnostics.into_iter().map(|d| d.error).collect(),
+    }
+}
+
+#[test]
+fn sample_deck_is_legal_in_standard() {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let (leader, main_deck, don_deck) =
+        parse_deck_list(&sample_deck_list(), &card_database, &standard()).unwrap();
+
+    assert_eq!(leader.identifier, "ST01-001");
+    assert_eq!(main_deck.len(), 50);
+    assert_eq!(don_deck.len(), 10);
+}
+
+#[test]
+fn format_rules_name_the_violated_rule() {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let deck_list = sample_deck_list();
+
+    let mut banned = standard();
+    banned.banned.push("ST01-015".to_string());
+    assert!(matches!(
+        errors(parse_deck_list(&deck_list, &card_database, &banned))[..],
+        [DeckError::BannedCard(ref id)] if id == "ST01-015"
+    ));
+
+    let mut restricted = standard();
+    restricted.restricted.insert("ST01-012".to_string(), 1);
+    assert!(matches!(
+        errors(parse_deck_list(&deck_list, &card_database, &restricted))[..],
+        [DeckError::RestrictedCard(ref id, 4, 1)] if id == "ST01-012"
+    ));
+
+    let mut rotated = standard();
+    rotated.min_block = 2;
+    let rotated_out = errors(parse_deck_list(&deck_list, &card_database, &rotated));
+    assert!(!rotated_out.is_empty());
+    assert!(rotated_out.iter().all(|e| matches!(e, DeckError::RotatedOut(_, 1))));
+}
+
+#[test]
+fn errata_replace_printed_values() {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+
+    let mut errata = standard();
+    errata.errata.insert(
+        "ST01-012".to_string(),
+        Errata {
+            power: Some(5000),
+            script: Some("rush".to_string()),
+            text: Some("[Rush]".to_string()),
+            ..Default::default()
+        },
+    );
+
+    let (_, main_deck, _) = parse_deck_list(&sample_deck_list(), &card_database, &errata).unwrap();
+    let luffy = main_deck.iter().find(|card| card.identifier == "ST01-012").unwrap();
+    assert_eq!(luffy.power.unwrap().0, 5000);
+    assert_eq!(luffy.effects.len(), 1);
+    assert_eq!(luffy.text, "[Rush]");
+}
+
+#[test]
+fn errata_with_a_broken_script_are_rejected() {
+    let mut format = standard();
+    format.errata.insert(
+        "ST01-012".to_string(),
+        Errata {
+            script: Some("rush\non_play: fly(1)".to_string()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(
+        format.validate().unwrap_err().to_string(),
+        "errata for ST01-012: script 2:10: unknown effect `fly`"
+    );
+
+    let path = std::env::temp_dir().join(format!("optcg-format-{}.json", std::process::id()));
+    let path = path.to_str().unwrap();
+    std::fs::write(path, serde_json::to_string(&format).unwrap()).unwrap();
+    let loaded = Format::load(path);
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(
+        loaded.unwrap_err().to_string(),
+        format!("{path}: errata for ST01-012: script 2:10: unknown effect `fly`")
+    );
+}
+
+#[test]
+fn main_deck_must_share_a_color_with_the_leader() {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+
+    // swap the Red Franky for the Purple one.
+    let deck_list = sample_deck_list().replace("4 ST01-010 Franky (P0)", "4 OP09-072 Franky (P1)");
+    assert!(matches!(
+        errors(parse_deck_list(&deck_list, &card_database, &standard()))[..],
+        [DeckError::ColorMismatch(ref id, _)] if id == "OP09-072"
+    ));
+
+    // a Red/Purple Leader can use both.
+    let (mut leader, main_deck, don_deck) =
+        parse_deck_list(&sample_deck_list(), &card_database, &standard()).unwrap();
+    leader.color.push(CardColor::Purple);
+    let purple_franky = card_database.get("OP09-072", "P1").unwrap();
+
+    let mut cards = vec![leader];
+    for card in main_deck {
+        match card.identifier.as_str() {
+            "ST01-010" => cards.push(purple_franky.clone()),
+            _ => cards.push(card),
+        }
+    }
+    cards.extend(don_deck);
+    assert!(validate_deck(&cards, &standard()).is_ok());
+}
+
+#[test]
+fn every_problem_is_reported_with_its_line() {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let deck_list = "// no leader, and some broken lines
+x4 ST01-006 Tony Tony Chopper (P0)
+4 ST01-999 Nobody (P0)
+4 ST01-007 Nami (P9)
+4 ST01-002 P0)
+
+4
+4 ST01-013 Roronoa Zoro (P0)
+";
+
+    let diagnostics = parse_deck_list(deck_list, &card_database, &standard()).unwrap_err();
+    let found: Vec<(usize, usize, &str)> = diagnostics
+        .iter()
+        .map(|d| (d.line, d.column, d.text.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (2, 1, "x4"),
+            (3, 3, "ST01-999"),
+            (4, 3, "ST01-007"),
+            (5, 12, "P0)"),
+            (7, 1, "4"),
+            (0, 0, ""),
+            (0, 0, ""),
+            (0, 0, ""),
+        ]
+    );
+    assert!(matches!(
+        errors(Err(diagnostics))[..],
+        [
+            DeckError::InvalidQuantity(_),
+            DeckError::InvalidCardId(_),
+            DeckError::InvalidCardArt(_),
+            DeckError::ArtSyntaxError(_),
+            DeckError::IncompleteLine(_),
+            // the cards that could be read are still checked as a deck.
+            DeckError::InvalidDeckLength(4),
+            DeckError::NoLeader,
+            DeckError::NotEnoughDon,
+        ]
+    ));
+}
+
+#[test]
+fn deck_without_a_leader_reports_no_leader() {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let deck_list = sample_deck_list().replace("1 ST01-001 Monkey D. Luffy (P0)", "");
+
+    let diagnostics = parse_deck_list(&deck_list, &card_database, &standard()).unwrap_err();
+    let errors: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+    assert_eq!(
+        errors,
+        vec![
+            "the deck has 60 cards, it needs 61 including the Leader and DON!!",
+            "the deck has no Leader"
+        ]
+    );
+}
+
+#[test]
+fn deck_lists_round_trip_through_every_format() {
+    use optcg::deck_formats::*;
+
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let (leader, mut main_deck, don_deck) =
+        parse_deck_list(&sample_deck_list(), &card_database, &standard()).unwrap();
+    // an alternate art has to survive too.
+    let nami = main_deck.iter().position(|card| card.identifier == "ST01-007").unwrap();
+    main_deck[nami].art = "P1".to_string();
+    let mut card_database = card_database;
+    card_database.insert(main_deck[nami].clone()).unwrap();
+
+    let expected = deck_list_entries(&leader, &main_deck, &don_deck);
+
+    for to in [DeckListFormat::Optcg, DeckListFormat::Simulator, DeckListFormat::Json] {
+        let exported = export_deck_list(&leader, &main_deck, &don_deck, to);
+        assert_eq!(detect_deck_list_format(&exported), to, "{exported}");
+
+        let (leader, main_deck, don_deck) = import_deck_list(&exported, &card_database, &standard())
+            .unwrap_or_else(|e| panic!("{to}: {e:?}\n{exported}"));
+        assert_eq!(deck_list_entries(&leader, &main_deck, &don_deck), expected, "{to}");
+    }
+
+    assert!(export_deck_list(&leader, &main_deck, &don_deck, DeckListFormat::Simulator)
+        .contains("1xST01-007_p1"));
+}
+
+#[test]
+fn deck_list_formats_are_detected() {
+    use optcg::deck_formats::*;
+
+    assert_eq!(detect_deck_list_format("// Luffy\n1xOP01-001\n4xOP01-006_p1\n"), DeckListFormat::Simulator);
+    assert_eq!(detect_deck_list_format("  [\"OP01-001\", \"OP01-006\"]"), DeckListFormat::Json);
+    assert_eq!(detect_deck_list_format(&sample_deck_list()), DeckListFormat::Optcg);
+    // a name with an `x` in it isn't a simulator line.
+    assert_eq!(detect_deck_list_format("4 ST01-006 Tony Tony Chopper"), DeckListFormat::Optcg);
+}
+
+#[test]
+fn imported_deck_lists_report_positions_in_the_original() {
+    use optcg::deck_formats::*;
+
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let positions = |deck_list: &str| -> Vec<(usize, usize, String)> {
+        import_deck_list(deck_list, &card_database, &standard())
+            .unwrap_err()
+            .into_iter()
+            .map(|d| (d.line, d.column, d.text))
+            .collect()
+    };
+
+    let json = "[\"ST01-001\", \"ST01-006\",\n \"ST01-999\", \"ST01-006\", \"ST01-999\"]";
+    assert_eq!(
+        positions(json),
+        vec![(2, 3, "ST01-999".to_string()), (0, 0, String::new())]
+    );
+
+    let simulator = "1xST01-001\n4xST01-999\n";
+    assert_eq!(positions(simulator), vec![(2, 3, "ST01-999".to_string()), (0, 0, String::new())]);
+
+    let unreadable = import_deck_list("[\"ST01-001\",\n ST01-006]", &card_database, &standard()).unwrap_err();
+    assert_eq!((unreadable[0].line, unreadable[0].column), (2, 2));
+    assert!(matches!(unreadable[..], [DeckDiagnostic { error: DeckError::UnreadableDeckList(_), .. }]));
+}
+
+#[test]
+fn deck_codes_round_trip_and_catch_typos() {
+    use optcg::deck_formats::*;
+
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let (leader, main_deck, don_deck) =
+        parse_deck_list(&sample_deck_list(), &card_database, &standard()).unwrap();
+
+    let code = encode_deck_code(&leader, &main_deck, &don_deck);
+    assert!(code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+    // the order cards are listed in doesn't change the code.
+    let mut reversed = main_deck.clone();
+    reversed.reverse();
+    assert_eq!(encode_deck_code(&leader, &reversed, &don_deck), code);
+
+    let (decoded_leader, decoded_main, decoded_don) =
+        decode_deck_code(&code, &card_database, &standard()).unwrap();
+    let mut expected = deck_list_entries(&leader, &main_deck, &don_deck);
+    let mut decoded = deck_list_entries(&decoded_leader, &decoded_main, &decoded_don);
+    expected.sort_by(|a, b| a.id.cmp(&b.id));
+    decoded.sort_by(|a, b| a.id.cmp(&b.id));
+    assert_eq!(decoded, expected);
+
+    let mut typo: Vec<char> = code.chars().collect();
+    typo[10] = if typo[10] == 'A' { 'B' } else { 'A' };
+    let typo: String = typo.into_iter().collect();
+    assert!(matches!(
+        decode_deck_code(&typo, &card_database, &standard()).unwrap_err()[..],
+        [DeckDiagnostic { error: DeckError::InvalidDeckCode(_), .. }]
+    ));
+    assert!(decode_deck_code("not a code!", &card_database, &standard()).is_err());
+
+    // shorter than the plain list of quantities and ids it stands for.
+    let plain: usize = expected.iter().map(|entry| format!("{} {}\n", entry.quantity, entry.id).len()).sum();
+    assert!(code.len() < plain, "{} vs {plain}", code.len());
+}
+
+#[test]
+fn deck_codes_keep_ids_without_a_set_whole() {
+    use optcg::deck_formats::*;
+
+    let mut card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let mut promo = card_database.get("ST01-006", "P0").unwrap().clone();
+    promo.identifier = "PROMO6".to_string();
+    card_database.insert(promo).unwrap();
+
+    let deck_list = sample_deck_list().replace("4 ST01-006 Tony Tony Chopper (P0)", "4 PROMO6");
+    let (leader, main_deck, don_deck) = parse_deck_list(&deck_list, &card_database, &standard()).unwrap();
+    let code = encode_deck_code(&leader, &main_deck, &don_deck);
+
+    let (decoded_leader, decoded_main, decoded_don) = decode_deck_code(&code, &card_database, &standard()).unwrap();
+    let mut expected = deck_list_entries(&leader, &main_deck, &don_deck);
+    let mut decoded = deck_list_entries(&decoded_leader, &decoded_main, &decoded_don);
+    expected.sort_by(|a, b| a.id.cmp(&b.id));
+    decoded.sort_by(|a, b| a.id.cmp(&b.id));
+    assert_eq!(decoded, expected);
+    assert!(decoded.iter().any(|entry| entry.id == "PROMO6"));
+}
+
+#[test]
+fn deck_stats_for_the_sample_deck() {
+    use optcg::deck_stats::*;
+
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let (leader, main_deck, _) = parse_deck_list(&sample_deck_list(), &card_database, &standard()).unwrap();
+    let stats = DeckStats::new(&leader, &main_deck);
+
+    assert_eq!(stats.cost_curve.values().sum::<usize>(), 50);
+    assert_eq!(stats.cost_curve[&2], 16);
+    assert_eq!((stats.counters_1000, stats.counters_2000), (24, 4));
+    assert_eq!(stats.counter_total, 32000);
+    assert_eq!(stats.blockers, 4);
+    assert_eq!(stats.triggers, 6);
+    assert_eq!(stats.colors["Red"], 50);
+    assert_eq!(stats.types["Straw Hat Crew"], 42);
+
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["power"]["5000"], 12);
+    assert!(stats.to_string().contains("Straw Hat Crew |    42"));
+}
+
+#[test]
+fn opening_hand_probability_matches_simulation() {
+    use optcg::probability::*;
+    use rand::prelude::*;
+
+    // 4 copies in a 50 card deck, seen in the opening 5.
+    assert!((hypergeometric_at_least(50, 4, 5, 1) - 0.3530).abs() < 0.0001);
+    assert_eq!(hypergeometric_at_least(50, 4, 5, 0), 1.0);
+    assert_eq!(hypergeometric_at_least(50, 4, 5, 5), 0.0);
+
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let (_, main_deck, _) = parse_deck_list(&sample_deck_list(), &card_database, &standard()).unwrap();
+    let two_cost_character = |card: &Card| matches!(card.category, CardCategory::Character) && card.cost.0 == 2;
+
+    let mut rng = StdRng::seed_from_u64(46);
+    let questions = [
+        HandQuestion::default(),
+        HandQuestion { mulligan: true, ..Default::default() },
+        HandQuestion { at_least: 2, turn: 3, going_first: false, ..Default::default() },
+    ];
+    for question in questions.iter() {
+        let exact = hand_probability(&main_deck, two_cost_character, question);
+        let simulated = simulate_hand_probability(&main_deck, two_cost_character, question, 10000, &mut rng).unwrap();
+        assert!((exact - simulated).abs() < 0.03, "{question:?}: {exact} vs {simulated}");
+    }
+
+    // a mulligan can only help.
+    assert!(
+        hand_probability(&main_deck, two_cost_character, &questions[1])
+            > hand_probability(&main_deck, two_cost_character, &questions[0])
+    );
+
+    // no games played is no estimate, rather than NaN.
+    assert_eq!(simulate_hand_probability(&main_deck, two_cost_character, &questions[0], 0, &mut rng), None);
+}
+
+#[test]
+fn deck_builder_validates_as_cards_are_added() {
+    use optcg::deck_builder::*;
+
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let format = standard();
+    let mut builder = DeckBuilder::new(&card_database, &format);
+    assert_eq!(builder.don_deck().len(), DON_DECK_SIZE);
+    assert!(matches!(builder.validate().unwrap_err()[..], [DeckError::InvalidDeckLength(10), DeckError::NoLeader]));
+
+    assert!(matches!(builder.set_leader("ST01-006", ""), Err(DeckError::NotALeader(_))));
+    // adding a Leader is a mistake whether or not the deck has one yet.
+    assert!(matches!(builder.add("ST01-001", "P0", 1), Err(DeckError::LeaderInMainDeck(ref id)) if id == "ST01-001"));
+    builder.set_leader("ST01-001", "P0").unwrap();
+    assert!(matches!(builder.add("ST01-001", "P0", 1), Err(DeckError::LeaderInMainDeck(_))));
+    assert!(matches!(builder.add("ST01-999", "P0", 1), Err(DeckError::InvalidCardId(_))));
+
+    // the Purple Franky can never go in a Red deck.
+    assert_eq!(builder.remaining_copies("OP09-072"), 0);
+    builder.add("ST01-006", "P0", 3).unwrap();
+    assert_eq!(builder.remaining_copies("ST01-006"), 1);
+
+    // going over the copy limit is allowed, and reported.
+    builder.add("ST01-006", "P0", 2).unwrap();
+    assert_eq!(builder.remaining_copies("ST01-006"), 0);
+    assert!(builder
+        .validate()
+        .unwrap_err()
+        .iter()
+        .any(|e| matches!(e, DeckError::TooManyCopies(id, 5) if id == "ST01-006")));
+    assert_eq!(builder.remove("ST01-006", "", 10), 5);
+
+    // building the sample deck card by card gives the same deck as the list.
+    let (leader, main_deck, don_deck) = parse_deck_list(&sample_deck_list(), &card_database, &format).unwrap();
+    for entry in optcg::deck_formats::deck_list_entries(&leader, &main_deck, &vec![]).iter().skip(1) {
+        assert_eq!(builder.remaining_copies(&entry.id), builder.remaining_slots().min(4) as i32);
+        builder.add(&entry.id, &entry.art, entry.quantity).unwrap();
+    }
+    assert_eq!(builder.remaining_slots(), 0);
+    assert_eq!(builder.remaining_copies("ST01-006"), 0);
+
+    let (built_leader, built_main, built_don) = builder.build().unwrap();
+    assert_eq!(built_leader.identifier, leader.identifier);
+    assert_eq!(built_main.len(), main_deck.len());
+    assert_eq!(built_don.len(), don_deck.len());
+
+    let edited = DeckBuilder::from_deck(&card_database, &format, &leader, &main_deck, &don_deck);
+    assert!(edited.is_legal());
+}
+
+#[test]
+fn deck_diff_counts_arts_as_one_card() {
+    use optcg::deck_history::*;
+
+    let before = sample_deck_list();
+    let after = before
+        .replace("4 ST01-007 Nami (P0)", "2 ST01-007 Nami (P0)\n2 ST01-007 Nami (P1)")
+        .replace("4 ST01-002 Usopp (P0)", "3 ST01-002 Usopp (P0)")
+        .replace("1 ST01-014 Guard Point (P0)", "2 ST01-014 Guard Point (P0)");
+
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let diff = diff_deck_lists(&before, &after, &card_database).unwrap();
+    let changes: Vec<(&str, i32)> = diff.iter().map(|card| (card.id.as_str(), card.change())).collect();
+    assert_eq!(changes, vec![("ST01-002", -1), ("ST01-007", 0), ("ST01-014", 1)]);
+    assert_eq!(diff[1].arts.len(), 2);
+    assert_eq!(diff[1].to_string(), "+0 ST01-007  4 -> 4  (P0 -2, P1 +2)");
+    assert_eq!(diff[0].to_string(), "-1 ST01-002  4 -> 3");
+
+    // the same deck in another format has no changes.
+    let (leader, main_deck, don_deck) = parse_deck_list(&before, &card_database, &standard()).unwrap();
+    let simulator = optcg::deck_formats::export_deck_list(
+        &leader,
+        &main_deck,
+        &don_deck,
+        optcg::deck_formats::DeckListFormat::Simulator,
+    );
+    assert_eq!(diff_deck_lists(&before, &simulator, &card_database).unwrap(), vec![]);
+
+    let path = std::env::temp_dir().join(format!("optcg-deck-history-{}.json", std::process::id()));
+    let path = path.to_str().unwrap();
+    let mut history = DeckHistory::load(path).unwrap();
+    history.add("first", read_deck_list_entries(&before).unwrap()).unwrap();
+    history.add("second", read_deck_list_entries(&after).unwrap()).unwrap();
+    assert!(history.add("first", vec![]).is_err());
+    history.save(path).unwrap();
+
+    let history = DeckHistory::load(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(history.diff("first", "second", &card_database).unwrap(), diff);
+    assert!(history.diff("first", "third", &card_database).is_err());
+}
+
+#[test]
+fn deck_diff_matches_a_line_without_an_art_to_the_base_art() {
+    use optcg::deck_history::*;
+
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    // OP09-072 has no P0 art, so P1 is its base art.
+    let before = read_deck_list_entries("2 OP09-072").unwrap();
+    let after = read_deck_list_entries("2 OP09-072 (P1)").unwrap();
+    assert_eq!(diff_deck_list_entries(&before, &after, &card_database), vec![]);
+
+    let after = read_deck_list_entries("3 OP09-072 (P1)").unwrap();
+    let diff = diff_deck_list_entries(&before, &after, &card_database);
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff[0].to_string(), "+1 OP09-072  2 -> 3");
+}
+
+#[test]
+fn art_variants_fall_back_to_the_base_art_and_share_copy_limits() {
+    let mut card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let deck_list = sample_deck_list();
+
+    // OP09-072 only has art P1, which a line without an art picks.
+    let franky = |line: &str| deck_list.replace("4 ST01-010 Franky (P0)", line);
+    assert!(matches!(
+        errors(parse_deck_list(&franky("4 OP09-072"), &card_database, &standard()))[..],
+        [DeckError::ColorMismatch(ref id, _)] if id == "OP09-072"
+    ));
+    assert!(matches!(
+        errors(parse_deck_list(&franky("4 OP09-072 Franky (P0)"), &card_database, &standard()))[..],
+        [DeckError::InvalidCardArt(_), DeckError::InvalidDeckLength(57)]
+    ));
+    let simulator = franky("4xOP09-072").replace("4 ST01-010", "4xST01-010");
+    let simulator = optcg::deck_formats::to_optcg_deck_list(&simulator, optcg::deck_formats::DeckListFormat::Simulator);
+    assert!(matches!(
+        errors(parse_deck_list(&simulator.unwrap(), &card_database, &standard()))[..],
+        [DeckError::ColorMismatch(ref id, _)] if id == "OP09-072"
+    ));
+
+    // copies of every art count towards the same limit.
+    let mut chopper = card_database.get("ST01-006", "P0").unwrap().clone();
+    chopper.art = "P1".to_string();
+    card_database.insert(chopper).unwrap();
+    let deck_list = deck_list
+        .replace(
+            "4 ST01-006 Tony Tony Chopper (P0)",
+            "3 ST01-006 Tony Tony Chopper (P0)\n2 ST01-006 Tony Tony Chopper (P1)",
+        )
+        .replace("4 ST01-007 Nami (P0)", "3 ST01-007 Nami (P0)");
+    assert!(matches!(
+        errors(parse_deck_list(&deck_list, &card_database, &standard()))[..],
+        [DeckError::TooManyCopies(ref id, 5)] if id == "ST01-006"
+    ));
+}
@@ -0,0 +1,304 @@
+use optcg::card::*;
+use optcg::game::*;
+use optcg::player::*;
+use optcg::player_area::*;
+use optcg::utils::load_card;
+
+fn card(id: &str) -> Card {
+    let path = format!("assets/card_data/{id}.json");
+    load_card(&std::fs::read_to_string(&path).unwrap()).unwrap()
+}
+
+// A board whose life cards are `life`, the last one on top.
+fn player_area(life: Vec<Card>) -> PlayerArea {
+    PlayerArea {
+        player: Player::empty(),
+        life: life.into_iter().map(|c| c.set_facedown()).collect(),
+        stage: vec![],
+        character: vec![],
+        rested_character: vec![],
+        active_don: vec![],
+        rested_don: vec![],
+        leader_rested: false,
+        lost: false,
+    }
+}
+
+#[test]
+fn double_attack_removes_two_life_cards() {
+    let mut attacker = card("ST01-004");
+    assert_eq!(attacker.damage(), 1);
+    attacker.effects.push(Effect::DoubleAttack);
+    assert_eq!(attacker.damage(), 2);
+
+    // ST01-002 has a [Trigger], ST01-004 doesn't.
+    let area = player_area(vec![card("ST01-004"), card("ST01-004"), card("ST01-002")]);
+    let (area, triggers) = area.take_life_damage(attacker.damage(), false);
+
+    assert_eq!(area.life.len(), 1);
+    assert_eq!(area.player.hand.len(), 1);
+    assert_eq!(area.player.hand[0].identifier, "ST01-004");
+    assert!(matches!(area.player.hand[0].facing, Facing::FaceUp));
+    assert_eq!(triggers.len(), 1);
+    assert_eq!(triggers[0].identifier, "ST01-002");
+    assert!(area.player.trash.is_empty());
+}
+
+#[test]
+fn banished_life_cards_are_trashed_without_triggers() {
+    let mut attacker = card("ST01-004");
+    attacker.effects.push(Effect::Banish);
+    assert!(attacker.has_keyword(Keyword::Banish));
+
+    let area = player_area(vec![card("ST01-004"), card("ST01-002")]);
+    let (area, triggers) = area.take_life_damage(attacker.damage(), attacker.has_keyword(Keyword::Banish));
+
+    assert!(triggers.is_empty());
+    assert_eq!(area.life.len(), 1);
+    assert!(area.player.hand.is_empty());
+    assert_eq!(area.player.trash.len(), 1);
+    assert_eq!(area.player.trash[0].identifier, "ST01-002");
+}
+
+#[test]
+fn damage_stops_when_life_runs_out() {
+    let mut attacker = card("ST01-004");
+    attacker.effects.push(Effect::DoubleAttack);
+
+    let area = player_area(vec![card("ST01-004")]);
+    let (area, triggers) = area.take_life_damage(attacker.damage(), false);
+
+    assert!(area.life.is_empty());
+    assert_eq!(area.player.hand.len(), 1);
+    assert!(triggers.is_empty());
+}
+
+#[test]
+fn damage_with_no_life_left_loses_the_game() {
+    let area = player_area(vec![card("ST01-004")]);
+    let (area, _) = area.take_life_damage(1, false);
+    assert!(area.life.is_empty());
+    assert!(!area.lost);
+
+    let (area, triggers) = area.take_life_damage(1, false);
+    assert!(area.lost);
+    assert!(triggers.is_empty());
+    assert_eq!(area.player.hand.len(), 1);
+}
+
+#[test]
+fn characters_played_this_turn_need_rush_to_attack() {
+    let mut character = card("ST01-004");
+    assert!(character.can_attack());
+
+    character.played_this_turn = true;
+    assert!(!character.can_attack());
+
+    character.effects.push(Effect::Rush);
+    assert!(character.can_attack());
+}
+
+#[test]
+fn only_triggers_the_engine_can_resolve_are_offered() {
+    // [Trigger] Play this card.
+    assert!(GameState::can_resolve_trigger(&card("ST01-002")));
+    // [Trigger] K.O. up to 1 of your opponent's Characters with 6000 power or less.
+    assert!(GameState::can_resolve_trigger(&card("ST01-015")));
+    // [Trigger] +1000 power during this turn, which isn't tracked yet.
+    assert!(!GameState::can_resolve_trigger(&card("ST01-014")));
+    assert!(!GameState::can_resolve_trigger(&card("ST01-004")));
+}
+
+// A Character with a single replacement effect.
+fn with_replacement(event: Replaceable, cost: EffectCost, effects: Vec<Effect>) -> Card {
+    let mut character = card("ST01-004");
+    character.effects = vec![Effect::Replacement(event, cost, effects)];
+    character
+}
+
+#[test]
+fn replacement_effects_stand_in_for_their_own_event() {
+    let mut area = player_area(vec![]);
+    area.character = vec![
+        with_replacement(Replaceable::KnockOut, EffectCost::Zero, vec![Effect::Draw(1)]),
+        with_replacement(Replaceable::LeaveField, EffectCost::Zero, vec![Effect::Draw(2)]),
+    ];
+
+    let knock_out = area.replacement_effects(&[Replaceable::KnockOut], Some(0));
+    assert!(matches!(knock_out[..], [Effect::Replacement(Replaceable::KnockOut, _, _)]));
+    assert!(area.replacement_effects(&[Replaceable::LeaveField], Some(0)).is_empty());
+    assert!(area.replacement_effects(&[Replaceable::Damage], Some(0)).is_empty());
+
+    // a K.O. also removes the Character from the field, so both are offered together.
+    let both = area.replacement_effects(&[Replaceable::KnockOut, Replaceable::LeaveField], None);
+    assert_eq!(both.len(), 2);
+}
+
+#[test]
+fn replacement_effects_only_come_from_the_affected_character() {
+    let mut area = player_area(vec![]);
+    area.character = vec![
+        card("ST01-004"),
+        with_replacement(Replaceable::KnockOut, EffectCost::Zero, vec![Effect::Draw(1)]),
+    ];
+    area.rested_character = vec![with_replacement(Replaceable::Damage, EffectCost::Zero, vec![Effect::Draw(1)])];
+
+    assert!(area.replacement_effects(&[Replaceable::KnockOut], Some(0)).is_empty());
+    assert_eq!(area.replacement_effects(&[Replaceable::KnockOut], Some(1)).len(), 1);
+    assert!(area.replacement_effects(&[Replaceable::KnockOut], Some(2)).is_empty());
+
+    // damage to the Leader can be replaced by any card on the board, rested ones included.
+    assert_eq!(area.replacement_effects(&[Replaceable::Damage], None).len(), 1);
+}
+
+#[test]
+fn replacement_effects_need_a_payable_cost() {
+    let mut area = player_area(vec![]);
+    area.character = vec![
+        with_replacement(Replaceable::KnockOut, EffectCost::RestDon(1), vec![Effect::Draw(1)]),
+        with_replacement(Replaceable::KnockOut, EffectCost::DonAttached(1), vec![Effect::Draw(1)]),
+    ];
+    assert!(area.replacement_effects(&[Replaceable::KnockOut], Some(0)).is_empty());
+    assert!(area.replacement_effects(&[Replaceable::KnockOut], Some(1)).is_empty());
+
+    area.active_don.push(card("DON-don"));
+    area.character[1].attached_don.push(card("DON-don"));
+    assert_eq!(area.replacement_effects(&[Replaceable::KnockOut], Some(0)).len(), 1);
+    assert_eq!(area.replacement_effects(&[Replaceable::KnockOut], Some(1)).len(), 1);
+
+    let area = area.pay_effect_cost(EffectCost::RestDon(1));
+    assert!(area.replacement_effects(&[Replaceable::KnockOut], Some(0)).is_empty());
+    assert_eq!(area.rested_don.len(), 1);
+}
+
+#[test]
+fn replacement_effects_that_cannot_be_carried_out_are_not_offered() {
+    let mut area = player_area(vec![]);
+    area.character = vec![with_replacement(Replaceable::KnockOut, EffectCost::Zero, vec![Effect::PlusPower(1000)])];
+
+    assert!(area.replacement_effects(&[Replaceable::KnockOut], Some(0)).is_empty());
+}
+
+// A Character with a single continuous effect.
+fn with_static_effect(timing: Timing, cost: EffectCost, effects: Vec<Effect>) -> Card {
+    let mut character = card("ST01-004");
+    character.effects = vec![Effect::TimedEffect(timing, cost, effects)];
+    character
+}
+
+fn power(card: &Card) -> i32 {
+    card.current_power().unwrap().0
+}
+
+#[test]
+fn always_effects_apply_on_both_turns() {
+    let mut character = with_static_effect(
+        Timing::Always,
+        EffectCost::Zero,
+        vec![Effect::PlusPower(1000), Effect::MinusCost(1), Effect::Blocker],
+    );
+    let printed_power = power(&character);
+    let printed_cost = character.cost.0;
+
+    for owners_turn in [true, false] {
+        character.recompute_static_effects(owners_turn);
+        assert_eq!(power(&character), printed_power + 1000);
+        assert_eq!(character.current_cost().0, printed_cost - 1);
+        assert!(character.has_keyword(Keyword::Blocker));
+    }
+}
+
+#[test]
+fn turn_effects_only_apply_on_their_turn() {
+    let mut yours = with_static_effect(Timing::DuringTurn, EffectCost::Zero, vec![Effect::PlusPower(2000), Effect::Rush]);
+    let mut theirs = with_static_effect(Timing::DuringOpponentsTurn, EffectCost::Zero, vec![Effect::MinusCost(2)]);
+    let printed_power = power(&yours);
+    let printed_cost = theirs.cost.0;
+
+    yours.recompute_static_effects(true);
+    theirs.recompute_static_effects(true);
+    assert_eq!(power(&yours), printed_power + 2000);
+    assert!(yours.has_keyword(Keyword::Rush));
+    assert_eq!(theirs.current_cost().0, printed_cost);
+
+    yours.recompute_static_effects(false);
+    theirs.recompute_static_effects(false);
+    assert_eq!(power(&yours), printed_power);
+    assert!(!yours.has_keyword(Keyword::Rush));
+    assert_eq!(theirs.current_cost().0, (printed_cost - 2).max(0));
+}
+
+#[test]
+fn static_effects_wait_for_attached_don() {
+    let mut character = with_static_effect(Timing::DuringTurn, EffectCost::DonAttached(1), vec![Effect::DoubleAttack]);
+    character.recompute_static_effects(true);
+    assert!(!character.has_keyword(Keyword::DoubleAttack));
+
+    // the attached DON!! card also gives its own +1000.
+    let printed_power = power(&character);
+    character.attached_don.push(card("DON-don"));
+    character.recompute_static_effects(true);
+    assert!(character.has_keyword(Keyword::DoubleAttack));
+    assert_eq!(power(&character), printed_power + 1000);
+
+    character.recompute_static_effects(false);
+    assert!(!character.has_keyword(Keyword::DoubleAttack));
+    assert_eq!(power(&character), printed_power);
+}
+
+#[test]
+fn your_characters_effects_reach_the_whole_board() {
+    let mut area = player_area(vec![]);
+    area.stage = vec![with_static_effect(
+        Timing::DuringTurn,
+        EffectCost::Zero,
+        vec![Effect::YourCharacters(vec![Effect::PlusPower(1000), Effect::MinusCost(1)])],
+    )];
+    area.character = vec![card("ST01-004")];
+    area.rested_character = vec![card("ST01-004")];
+    area.player.hand = vec![card("ST01-004"), card("ST01-014")];
+    let printed_power = power(&area.character[0]);
+    let printed_cost = area.character[0].cost.0;
+
+    area.recompute_static_effects(true);
+    assert_eq!(power(&area.character[0]), printed_power + 1000);
+    assert_eq!(power(&area.rested_character[0]), printed_power + 1000);
+    assert_eq!(area.player.hand[0].current_cost().0, printed_cost - 1);
+    // Events aren't Characters.
+    assert_eq!(area.player.hand[1].current_cost().0, area.player.hand[1].cost.0);
+    // the effect is for the owner's Characters, not the Stage itself.
+    assert!(area.stage[0].status.is_empty());
+
+    area.recompute_static_effects(false);
+    assert_eq!(power(&area.character[0]), printed_power);
+    assert_eq!(area.player.hand[0].current_cost().0, printed_cost);
+}
+
+#[test]
+fn reported_decks_are_accepted_once_legal() {
+    use optcg::card_database::*;
+    use optcg::format::*;
+    use optcg::ServerMessage;
+
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+    let standard = Format::load_named(FORMAT_DIR, "standard").unwrap();
+    let legal = std::fs::read_to_string("sample_deck.txt").unwrap();
+
+    let (leader, main_deck, don_deck) = review_deck(&legal, &card_database, &standard).unwrap();
+    assert_eq!((leader.identifier.as_str(), main_deck.len(), don_deck.len()), ("ST01-001", 50, 10));
+
+    // one copy too many of a card.
+    let illegal = legal.replace("4 ST01-006", "5 ST01-006");
+    match review_deck(&illegal, &card_database, &standard) {
+        Err(ServerMessage::DeckRejected(errors)) => {
+            assert!(matches!(
+                errors[..],
+                [DeckError::TooManyCopies(ref id, 5), DeckError::InvalidDeckLength(62)] if id == "ST01-006"
+            ), "{errors:?}");
+        }
+        other => panic!("expected DeckRejected, got {other:?}"),
+    }
+
+    let fixed = illegal.replace("5 ST01-006", "4 ST01-006");
+    assert!(review_deck(&fixed, &card_database, &standard).is_ok());
+}
@@ -0,0 +1,65 @@
+use optcg::card_database::*;
+use optcg::locale::*;
+
+#[test]
+fn every_locale_has_every_message() {
+    let english = MessageCatalogue::load(LOCALE_DIR, Locale::En).unwrap();
+
+    let japanese = MessageCatalogue::load(LOCALE_DIR, Locale::Ja).unwrap();
+
+    let mut missing: Vec<&str> = english.keys().filter(|key| japanese.keys().all(|k| k != *key)).collect();
+    let mut extra: Vec<&str> = japanese.keys().filter(|key| english.keys().all(|k| k != *key)).collect();
+    missing.sort();
+    extra.sort();
+
+    assert!(missing.is_empty(), "ja is missing {missing:?}");
+    assert!(extra.is_empty(), "ja has unknown messages {extra:?}");
+}
+
+#[test]
+fn english_deck_errors_match_their_display() {
+    use optcg::card::DeckError::*;
+
+    let english = MessageCatalogue::load(LOCALE_DIR, Locale::En).unwrap();
+    let errors = [
+        ArtSyntaxError("P1".into()),
+        NoLeader,
+        TooManyLeaders,
+        TooManyCopies("ST01-002".into(), 5),
+        IncompleteLine("4".into()),
+        TooManyFields("4 ST01-002 Usopp (P0) extra".into()),
+        InvalidCardId("ST01-999".into()),
+        InvalidCardName("Usop".into()),
+        InvalidCardArt("ST01-002 (P9)".into()),
+        InvalidDeckLength(57),
+        NotEnoughDon,
+        BannedCard("ST01-002".into()),
+        RestrictedCard("ST01-002".into(), 2, 1),
+        RotatedOut("ST01-002".into(), 1),
+        ColorMismatch("ST02-004".into(), "Capone Bege".into()),
+        InvalidQuantity("four".into()),
+        UnreadableDeckList("expected value at line 1 column 1".into()),
+        InvalidDeckCode("bad checksum".into()),
+        NotALeader("ST01-002".into()),
+        LeaderInMainDeck("ST01-001".into()),
+    ];
+    for error in errors.iter() {
+        assert_eq!(english.deck_error(error), error.to_string());
+    }
+
+    let japanese = MessageCatalogue::load(LOCALE_DIR, Locale::Ja).unwrap();
+    assert_eq!(japanese.deck_error(&BannedCard("ST01-002".into())), "ST01-002 は禁止カードです");
+}
+
+#[test]
+fn untranslated_card_text_falls_back_to_english() {
+    let card_database = CardDatabase::load(CARD_DATA_DIR).unwrap();
+
+    let usopp = card_database.get("ST01-002", "P0").unwrap();
+    assert_eq!(usopp.localised_name(Locale::Ja), "ウソップ");
+    assert_eq!(usopp.localised_name(Locale::En), "Usopp");
+
+    let carue = card_database.get("ST01-003", "P0").unwrap();
+    assert_eq!(carue.localised_text(Locale::Ja), carue.text);
+    assert_eq!(card_database.search("ウソップ").len(), 1);
+}
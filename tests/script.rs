@@ -0,0 +1,112 @@
+use optcg::game::*;
+use optcg::script::*;
+
+// Where `script` fails to parse, as `(line, column)`.
+fn error_at(script: &str) -> (usize, usize) {
+    let error = parse_script(script).unwrap_err();
+    (error.line, error.column)
+}
+
+#[test]
+fn timed_effects_parse_into_their_effect_tree() {
+    let effects = parse_script("when_attacking if don>=1: opponent_no_blocker(power>=5000)").unwrap();
+    assert!(matches!(
+        effects[..],
+        [Effect::TimedEffect(
+            Timing::WhenAttacking,
+            EffectCost::DonAttached(1),
+            ref actions,
+        )] if matches!(actions[..], [Effect::OpponentNoBlocker(Condition::PowerAndAbove(5000))])
+    ));
+
+    let effects = parse_script(
+        "# comment\n\nblocker\nactivate_main: once_per_turn, give_rested_don(1)\non_play if don-2: draw(2)\nmain if rest_don(1): ko(power<=6000)",
+    )
+    .unwrap();
+    assert_eq!(effects.len(), 4);
+    assert!(matches!(effects[0], Effect::Blocker));
+    assert!(matches!(
+        effects[1],
+        Effect::TimedEffect(Timing::ActivateMain, EffectCost::Zero, ref actions)
+            if matches!(actions[..], [Effect::OncePerTurn, Effect::GiveRestedDon(1)])
+    ));
+    assert!(matches!(
+        effects[2],
+        Effect::TimedEffect(Timing::OnPlay, EffectCost::MinusDon(2), ref actions)
+            if matches!(actions[..], [Effect::Draw(2)])
+    ));
+    assert!(matches!(
+        effects[3],
+        Effect::TimedEffect(Timing::Main, EffectCost::RestDon(1), ref actions)
+            if matches!(actions[..], [Effect::KnockOutWithPowerEqualOrLessThan(6000)])
+    ));
+}
+
+#[test]
+fn instead_of_parses_into_a_replacement() {
+    let effects = parse_script("instead_of ko if rest_don(1): draw(1)\ninstead_of leave_field:\ninstead_of damage: draw(2)").unwrap();
+    assert!(matches!(
+        effects[0],
+        Effect::Replacement(Replaceable::KnockOut, EffectCost::RestDon(1), ref actions)
+            if matches!(actions[..], [Effect::Draw(1)])
+    ));
+    // a replacement may do nothing but stop the event.
+    assert!(matches!(
+        effects[1],
+        Effect::Replacement(Replaceable::LeaveField, EffectCost::Zero, ref actions) if actions.is_empty()
+    ));
+    assert!(matches!(
+        effects[2],
+        Effect::Replacement(Replaceable::Damage, EffectCost::Zero, ref actions)
+            if matches!(actions[..], [Effect::Draw(2)])
+    ));
+
+    assert_eq!(error_at("instead_of attack: draw(1)"), (1, 12));
+    // unlike a replacement, a timed effect needs something to do.
+    assert_eq!(error_at("on_play:"), (1, 9));
+}
+
+#[test]
+fn errors_point_at_the_line_and_column() {
+    // lines are counted with comments and blank lines, columns from 1.
+    let error = parse_script("blocker\n# comment\n\non_play: fly(1)").unwrap_err();
+    assert_eq!((error.line, error.column), (4, 10));
+    assert_eq!(error.message, "unknown effect `fly`");
+    assert_eq!(error.to_string(), "4:10: unknown effect `fly`");
+
+    let cases = [
+        ("on_play: draw(99999999999)", (1, 15), "number `99999999999` is too large"),
+        ("on_play: draw(1) & rush", (1, 18), "unexpected character `&`"),
+        ("sometimes: draw(1)", (1, 1), "unknown keyword or timing `sometimes`"),
+        ("on_play if life(1): draw(1)", (1, 12), "unknown cost `life`"),
+        ("on_play if don 1: draw(1)", (1, 16), "expected `>=` or `-` after `don`"),
+        ("on_play: draw(1", (1, 16), "expected `)`, found end of line"),
+        ("on_play draw(1)", (1, 9), "expected `:`, found `draw`"),
+        ("on_play: draw(x)", (1, 15), "expected a number, found `x`"),
+        ("on_play: draw(1) rush", (1, 18), "unexpected `rush` after effect"),
+        ("on_play: ko(cost<=3)", (1, 13), "expected `power`, found `cost`"),
+        ("  42", (1, 3), "expected a keyword, timing or `instead_of`, found `42`"),
+    ];
+    for (script, position, message) in cases {
+        let error = parse_script(script).unwrap_err();
+        assert_eq!((error.line, error.column), position, "{script}");
+        assert_eq!(error.message, message, "{script}");
+    }
+}
+
+#[test]
+fn your_characters_wraps_the_effects_it_gives() {
+    let effects = parse_script("your_turn if don>=1: your_characters(plus_power(1000), blocker)").unwrap();
+    assert!(matches!(
+        effects[0],
+        Effect::TimedEffect(Timing::DuringTurn, EffectCost::DonAttached(1), ref actions)
+            if matches!(actions[..], [Effect::YourCharacters(ref given)]
+                if matches!(given[..], [Effect::PlusPower(1000), Effect::Blocker]))
+    ));
+    assert_eq!(
+        effects[0].to_string(),
+        "[DON!! x1] [Your Turn] All of your Characters gain +1000 power and gain [Blocker]."
+    );
+
+    assert_eq!(error_at("always: your_characters(plus_power(1000)"), (1, 41));
+}